@@ -0,0 +1,251 @@
+use core::str::FromStr;
+use std::collections::HashMap;
+
+use regex::Regex;
+
+/// User defined parameter type of Cucumber Expressions, registered with `StepRegistry::parameter` of `gnerkinf`.
+/// `{NAME}` in an expression matches `REGEX` and the match is parsed with `FromStr`.
+pub trait Parameter: FromStr {
+    const NAME: &'static str;
+    const REGEX: &'static str;
+}
+
+/// Regex of a parameter type and how its match becomes the text handed to `FromStr`
+#[derive(Clone, Debug)]
+pub struct ParameterType {
+    regex: String,
+    quoted: bool,
+}
+
+impl ParameterType {
+    pub fn new(regex: &str) -> Self {
+        Self {
+            regex: regex.to_string(),
+            quoted: false,
+        }
+    }
+}
+
+/// `{int}`, `{float}`, `{word}`, `{string}` and the anonymous `{}`
+pub fn builtin_parameters() -> HashMap<String, ParameterType> {
+    let mut types = HashMap::new();
+    types.insert("int".into(), ParameterType::new(r"-?\d+"));
+    types.insert(
+        "float".into(),
+        ParameterType::new(r"-?(?:\d+(?:\.\d*)?|\.\d+)(?:[eE][-+]?\d+)?"),
+    );
+    types.insert("word".into(), ParameterType::new(r"[^\s]+"));
+    types.insert(
+        "string".into(),
+        ParameterType {
+            regex: r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'"#.into(),
+            quoted: true,
+        },
+    );
+    types.insert("".into(), ParameterType::new(".*"));
+    types
+}
+
+/// Cucumber Expression compiled to a regex, or a regex step pattern
+#[derive(Clone, Debug)]
+pub struct Expression {
+    regex: Regex,
+    /// Capture group and type of every parameter
+    parameters: Vec<(usize, ParameterType)>,
+}
+
+impl Expression {
+    pub fn compile(source: &str, types: &HashMap<String, ParameterType>) -> Result<Self, String> {
+        let mut compiler = Compiler {
+            types,
+            regex: String::from("^"),
+            parameters: Vec::new(),
+            group: 1,
+        };
+        for word in split_words(source) {
+            compiler.word(&word)?;
+        }
+        compiler.regex.push('$');
+        let regex = Regex::new(&compiler.regex).map_err(|error| error.to_string())?;
+        Ok(Self {
+            regex,
+            parameters: compiler.parameters,
+        })
+    }
+
+    /// Regex step pattern, every capture group is a parameter
+    pub fn from_regex(source: &str) -> Result<Self, String> {
+        let regex = Regex::new(source).map_err(|error| error.to_string())?;
+        let parameters = (1..regex.captures_len())
+            .map(|group| (group, ParameterType::new("")))
+            .collect();
+        Ok(Self { regex, parameters })
+    }
+
+    pub fn parameter_count(&self) -> usize {
+        self.parameters.len()
+    }
+
+    /// Text of every parameter if `text` matches
+    pub fn captures(&self, text: &str) -> Option<Vec<String>> {
+        let captures = self.regex.captures(text)?;
+        Some(
+            self.parameters
+                .iter()
+                .map(|(group, parameter)| {
+                    let capture = captures.get(*group).map_or("", |capture| capture.as_str());
+                    if parameter.quoted {
+                        unquote(capture)
+                    } else {
+                        capture.to_string()
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+struct Compiler<'a> {
+    types: &'a HashMap<String, ParameterType>,
+    regex: String,
+    parameters: Vec<(usize, ParameterType)>,
+    /// Index of the next capture group
+    group: usize,
+}
+
+impl Compiler<'_> {
+    /// A whitespace separated word, `a/b` alternatives only span a single word
+    fn word(&mut self, word: &str) -> Result<(), String> {
+        let alternatives = split_unescaped(word, '/');
+        if alternatives.len() == 1 {
+            return self.part(word, true);
+        }
+        self.regex.push_str("(?:");
+        for (index, alternative) in alternatives.iter().enumerate() {
+            if alternative.is_empty() {
+                return Err(format!("empty alternative in `{word}`"));
+            }
+            if index > 0 {
+                self.regex.push('|');
+            }
+            self.part(alternative, false)?;
+        }
+        self.regex.push(')');
+        Ok(())
+    }
+
+    fn part(&mut self, part: &str, parameters: bool) -> Result<(), String> {
+        let mut chars = part.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    let escaped = chars.next().ok_or("expression ends with `\\`")?;
+                    self.literal(escaped);
+                }
+                '{' => {
+                    let mut closed = false;
+                    let name: String = chars
+                        .by_ref()
+                        .take_while(|&c| {
+                            closed = c == '}';
+                            !closed
+                        })
+                        .collect();
+                    if !closed {
+                        return Err(format!("unterminated parameter `{{{name}`"));
+                    }
+                    if !parameters {
+                        return Err(format!("parameter `{{{name}}}` in an alternative"));
+                    }
+                    let parameter = self
+                        .types
+                        .get(&name)
+                        .ok_or_else(|| format!("undefined parameter type `{{{name}}}`"))?
+                        .clone();
+                    let groups = Regex::new(&parameter.regex)
+                        .map_err(|error| error.to_string())?
+                        .captures_len();
+                    self.regex.push_str(&format!("({})", parameter.regex));
+                    self.parameters.push((self.group, parameter));
+                    self.group += groups;
+                }
+                '(' => {
+                    let mut optional = String::new();
+                    while let Some(c) = chars.next() {
+                        match c {
+                            ')' => break,
+                            '\\' => optional.extend(chars.next()),
+                            '{' => return Err("parameter in optional text".into()),
+                            c => optional.push(c),
+                        }
+                    }
+                    self.regex
+                        .push_str(&format!("(?:{})?", regex::escape(&optional)));
+                }
+                c => self.literal(c),
+            }
+        }
+        Ok(())
+    }
+
+    fn literal(&mut self, c: char) {
+        self.regex
+            .push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+    }
+}
+
+/// Words and the whitespace between them, ignoring whitespace inside `{}` and `()`
+fn split_words(source: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0;
+    let mut escaped = false;
+    for c in source.chars() {
+        let boundary = !escaped && depth == 0 && c.is_whitespace() != word_is_space(&word);
+        if boundary && !word.is_empty() {
+            words.push(core::mem::take(&mut word));
+        }
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' | '(' => depth += 1,
+            '}' | ')' => depth -= 1,
+            _ => (),
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn word_is_space(word: &str) -> bool {
+    word.starts_with(char::is_whitespace)
+}
+
+fn split_unescaped(word: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut escaped = false;
+    let mut depth = 0;
+    for c in word.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' | '(' => depth += 1,
+            '}' | ')' => depth -= 1,
+            c if c == separator && depth == 0 => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => (),
+        }
+        parts.last_mut().expect("at least one part").push(c);
+    }
+    parts
+}
+
+fn unquote(capture: &str) -> String {
+    let inner = &capture[1..capture.len() - 1];
+    inner.replace("\\\"", "\"").replace("\\'", "'")
+}
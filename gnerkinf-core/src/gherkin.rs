@@ -0,0 +1,309 @@
+use crate::{
+    language::{Language, ENGLISH},
+    Keyword,
+};
+
+#[derive(Debug)]
+pub struct Feature {
+    pub background: Vec<Step>,
+    pub scenarios: Vec<Scenario>,
+}
+
+#[derive(Debug)]
+pub struct Scenario {
+    pub name: String,
+    pub line: usize,
+    pub tags: Vec<String>,
+    pub steps: Vec<Step>,
+    pub examples: Vec<Examples>,
+}
+
+#[derive(Debug)]
+pub struct Examples {
+    /// Empty for an unnamed `Examples:` section
+    pub name: String,
+    pub tags: Vec<String>,
+    pub header: Vec<String>,
+    pub rows: Vec<Row>,
+}
+
+#[derive(Debug)]
+pub struct Row {
+    pub line: usize,
+    pub cells: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Step {
+    /// Keyword as written, `*` is read as `And`
+    pub keyword: Keyword,
+    /// Given, When or Then, with `And` and `But` taking the kind of the step before them
+    pub kind: Keyword,
+    pub text: String,
+    pub line: usize,
+}
+
+/// Scenario, or example of an outline, with the background steps in front
+pub struct Case {
+    pub name: String,
+    pub title: String,
+    pub line: usize,
+    pub ignored: bool,
+    pub steps: Vec<Step>,
+}
+
+/// Error with the line of the feature file it was found on
+#[derive(Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+enum Section {
+    Start,
+    Background,
+    Scenario,
+    Examples,
+}
+
+/// Parses the subset of Gherkin the generated tests support:
+/// a feature with an optional background, scenarios and scenario outlines with examples tables.
+/// Doc strings, data tables and rules are rejected.
+pub fn parse(source: &str) -> Result<Feature, ParseError> {
+    let language = Language::from_header(source).unwrap_or(&ENGLISH);
+    let mut feature = Feature {
+        background: Vec::new(),
+        scenarios: Vec::new(),
+    };
+    let mut section = Section::Start;
+    let mut tags = Vec::new();
+    let mut feature_tags = Vec::new();
+    let mut description = true;
+    let mut previous_kind = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        let error = |message: String| ParseError {
+            line: number,
+            message,
+        };
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('@') {
+            tags.extend(line.split_whitespace().map(str::to_string));
+            continue;
+        }
+        if line.starts_with("\"\"\"") || line.starts_with("```") {
+            return Err(error("doc strings are not supported".into()));
+        }
+        if line.starts_with('|') {
+            let Section::Examples = section else {
+                return Err(error("data tables are not supported".into()));
+            };
+            let cells = table_row(line).ok_or_else(|| error("unterminated table row".into()))?;
+            let examples = feature
+                .scenarios
+                .last_mut()
+                .and_then(|scenario| scenario.examples.last_mut())
+                .expect("examples section belongs to a scenario");
+            // the description of the examples ends with their table
+            description = false;
+            if examples.header.is_empty() {
+                examples.header = cells;
+            } else if cells.len() != examples.header.len() {
+                return Err(error(format!(
+                    "expected {} cells like the examples header, found {}",
+                    examples.header.len(),
+                    cells.len()
+                )));
+            } else {
+                examples.rows.push(Row {
+                    line: number,
+                    cells,
+                });
+            }
+            continue;
+        }
+        if header(line, language.feature).is_some() {
+            feature_tags = core::mem::take(&mut tags);
+            description = true;
+            continue;
+        }
+        if header(line, language.background).is_some() {
+            section = Section::Background;
+            description = true;
+            previous_kind = None;
+            continue;
+        }
+        if let Some(name) =
+            header(line, language.scenario_outline).or_else(|| header(line, language.scenario))
+        {
+            let mut scenario_tags = feature_tags.clone();
+            scenario_tags.append(&mut tags);
+            feature.scenarios.push(Scenario {
+                name: name.to_string(),
+                line: number,
+                tags: scenario_tags,
+                steps: Vec::new(),
+                examples: Vec::new(),
+            });
+            section = Section::Scenario;
+            description = true;
+            previous_kind = None;
+            continue;
+        }
+        if let Some(name) = header(line, language.examples) {
+            let Some(scenario) = feature.scenarios.last_mut() else {
+                return Err(error("examples outside of a scenario outline".into()));
+            };
+            scenario.examples.push(Examples {
+                name: name.to_string(),
+                tags: core::mem::take(&mut tags),
+                header: Vec::new(),
+                rows: Vec::new(),
+            });
+            section = Section::Examples;
+            description = true;
+            continue;
+        }
+        if let Some((keyword, text)) = step(line, language) {
+            let kind = match keyword {
+                Keyword::And | Keyword::But => previous_kind.ok_or_else(|| {
+                    error(format!(
+                        "`{}` needs a step before it",
+                        line_keyword(line, text)
+                    ))
+                })?,
+                kind => kind,
+            };
+            previous_kind = kind.into();
+            let step = Step {
+                keyword,
+                kind,
+                text: text.to_string(),
+                line: number,
+            };
+            match section {
+                Section::Background => feature.background.push(step),
+                Section::Scenario => feature
+                    .scenarios
+                    .last_mut()
+                    .expect("scenario section belongs to a scenario")
+                    .steps
+                    .push(step),
+                Section::Start | Section::Examples => {
+                    return Err(error("step outside of a scenario".into()))
+                }
+            }
+            description = false;
+            continue;
+        }
+        if !description {
+            return Err(error(format!("unexpected line `{line}`")));
+        }
+    }
+    Ok(feature)
+}
+
+impl Feature {
+    /// Every scenario and every example row of the outlines, `@ignore` tags mark them ignored
+    pub fn cases(self) -> Vec<Case> {
+        let mut cases = Vec::new();
+        for scenario in self.scenarios {
+            let ignored = scenario.tags.iter().any(|tag| tag == "@ignore");
+            let mut steps = self.background.clone();
+            if scenario.examples.is_empty() {
+                steps.extend(scenario.steps);
+                cases.push(Case {
+                    name: scenario.name.clone(),
+                    title: scenario.name,
+                    line: scenario.line,
+                    ignored,
+                    steps,
+                });
+                continue;
+            }
+            let mut index = 0;
+            for examples in &scenario.examples {
+                let ignored = ignored || examples.tags.iter().any(|tag| tag == "@ignore");
+                for (group_index, row) in examples.rows.iter().enumerate() {
+                    index += 1;
+                    let mut steps = steps.clone();
+                    steps.extend(scenario.steps.iter().map(|step| {
+                        let mut text = step.text.clone();
+                        for (name, value) in examples.header.iter().zip(&row.cells) {
+                            text = text.replace(&format!("<{name}>"), value);
+                        }
+                        Step {
+                            text,
+                            // point at the row, that's where the step text came from
+                            line: row.line,
+                            ..step.clone()
+                        }
+                    }));
+                    cases.push(Case {
+                        name: format!("{} example {index}", scenario.name),
+                        title: if examples.name.is_empty() {
+                            format!("{} (Example #{index})", scenario.name)
+                        } else {
+                            format!("{} ({} #{})", scenario.name, examples.name, group_index + 1)
+                        },
+                        line: row.line,
+                        ignored,
+                        steps,
+                    });
+                }
+            }
+        }
+        cases
+    }
+}
+
+/// Text after `keyword:` if the line starts with one of `keywords`
+fn header<'a>(line: &'a str, keywords: &[&str]) -> Option<&'a str> {
+    keywords
+        .iter()
+        .find_map(|keyword| line.strip_prefix(keyword)?.strip_prefix(':').map(str::trim))
+}
+
+fn step<'a>(line: &'a str, language: &Language) -> Option<(Keyword, &'a str)> {
+    if let Some(text) = line.strip_prefix("* ") {
+        return Some((Keyword::And, text.trim()));
+    }
+    let mut candidates: Vec<(Keyword, &str)> = [
+        (Keyword::Given, language.given),
+        (Keyword::When, language.when),
+        (Keyword::Then, language.then),
+        (Keyword::And, language.and),
+        (Keyword::But, language.but),
+    ]
+    .into_iter()
+    .flat_map(|(keyword, words)| words.iter().map(move |word| (keyword, *word)))
+    .collect();
+    // "Et que" has to be tried before "Et"
+    candidates.sort_by_key(|(_, word)| core::cmp::Reverse(word.len()));
+    candidates.into_iter().find_map(|(keyword, word)| {
+        let rest = line.strip_prefix(word)?;
+        if word.ends_with('\'') {
+            return Some((keyword, rest.trim()));
+        }
+        rest.starts_with(char::is_whitespace)
+            .then(|| (keyword, rest.trim()))
+    })
+}
+
+fn line_keyword<'a>(line: &'a str, text: &str) -> &'a str {
+    line[..line.len() - text.len()].trim()
+}
+
+fn table_row(line: &str) -> Option<Vec<String>> {
+    let inner = line.strip_prefix('|')?.strip_suffix('|')?;
+    Some(
+        inner
+            .split('|')
+            .map(|cell| cell.trim().to_string())
+            .collect(),
+    )
+}
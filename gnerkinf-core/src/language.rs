@@ -0,0 +1,208 @@
+use crate::Keyword;
+
+/// Gherkin keywords of a single language, taken from the official
+/// [gherkin-languages.json](https://github.com/cucumber/gherkin/blob/main/gherkin-languages.json).
+/// The first keyword of each kind is the one used in output, all of them are accepted when parsing.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub feature: &'static [&'static str],
+    pub background: &'static [&'static str],
+    pub scenario: &'static [&'static str],
+    pub scenario_outline: &'static [&'static str],
+    pub examples: &'static [&'static str],
+    pub given: &'static [&'static str],
+    pub when: &'static [&'static str],
+    pub then: &'static [&'static str],
+    pub and: &'static [&'static str],
+    pub but: &'static [&'static str],
+}
+
+pub static ENGLISH: Language = Language {
+    code: "en",
+    name: "English",
+    feature: &["Feature", "Business Need", "Ability"],
+    background: &["Background"],
+    scenario: &["Scenario", "Example"],
+    scenario_outline: &["Scenario Outline", "Scenario Template"],
+    examples: &["Examples", "Scenarios"],
+    given: &["Given"],
+    when: &["When"],
+    then: &["Then"],
+    and: &["And"],
+    but: &["But"],
+};
+
+pub static GERMAN: Language = Language {
+    code: "de",
+    name: "German",
+    feature: &["Funktionalität", "Funktion"],
+    background: &[
+        "Grundlage",
+        "Hintergrund",
+        "Voraussetzungen",
+        "Vorbedingungen",
+    ],
+    scenario: &["Szenario", "Beispiel"],
+    scenario_outline: &["Szenariogrundriss", "Szenarien"],
+    examples: &["Beispiele"],
+    given: &["Angenommen", "Gegeben sei", "Gegeben seien"],
+    when: &["Wenn"],
+    then: &["Dann"],
+    and: &["Und"],
+    but: &["Aber"],
+};
+
+pub static LITHUANIAN: Language = Language {
+    code: "lt",
+    name: "Lithuanian",
+    feature: &["Savybė"],
+    background: &["Kontekstas"],
+    scenario: &["Scenarijus", "Pavyzdys"],
+    scenario_outline: &["Scenarijaus šablonas"],
+    examples: &["Pavyzdžiai", "Scenarijai", "Variantai"],
+    given: &["Duota"],
+    when: &["Kai"],
+    then: &["Tada"],
+    and: &["Ir"],
+    but: &["Bet"],
+};
+
+pub static FRENCH: Language = Language {
+    code: "fr",
+    name: "French",
+    feature: &["Fonctionnalité"],
+    background: &["Contexte"],
+    scenario: &["Scénario", "Exemple"],
+    scenario_outline: &["Plan du scénario", "Plan du Scénario"],
+    examples: &["Exemples"],
+    given: &[
+        "Soit",
+        "Sachant que",
+        "Sachant qu'",
+        "Sachant",
+        "Etant donné que",
+        "Etant donné qu'",
+        "Etant donné",
+        "Etant donnée",
+        "Etant donnés",
+        "Etant données",
+        "Étant donné que",
+        "Étant donné qu'",
+        "Étant donné",
+        "Étant donnée",
+        "Étant donnés",
+        "Étant données",
+    ],
+    when: &["Quand", "Lorsque", "Lorsqu'"],
+    then: &["Alors", "Donc"],
+    and: &["Et que", "Et qu'", "Et"],
+    but: &["Mais que", "Mais qu'", "Mais"],
+};
+
+pub static SPANISH: Language = Language {
+    code: "es",
+    name: "Spanish",
+    feature: &["Característica", "Necesidad del negocio", "Requisito"],
+    background: &["Antecedentes"],
+    scenario: &["Escenario", "Ejemplo"],
+    scenario_outline: &["Esquema del escenario"],
+    examples: &["Ejemplos"],
+    given: &["Dado", "Dada", "Dados", "Dadas"],
+    when: &["Cuando"],
+    then: &["Entonces"],
+    and: &["Y", "E"],
+    but: &["Pero"],
+};
+
+pub static DUTCH: Language = Language {
+    code: "nl",
+    name: "Dutch",
+    feature: &["Functionaliteit"],
+    background: &["Achtergrond"],
+    scenario: &["Scenario", "Voorbeeld"],
+    scenario_outline: &["Abstract Scenario"],
+    examples: &["Voorbeelden"],
+    given: &["Gegeven", "Stel"],
+    when: &["Als", "Wanneer"],
+    then: &["Dan"],
+    and: &["En"],
+    but: &["Maar"],
+};
+
+pub static POLISH: Language = Language {
+    code: "pl",
+    name: "Polish",
+    feature: &["Właściwość", "Funkcja", "Aspekt", "Potrzeba biznesowa"],
+    background: &["Założenia"],
+    scenario: &["Scenariusz", "Przykład"],
+    scenario_outline: &["Szablon scenariusza"],
+    examples: &["Przykłady"],
+    given: &["Zakładając", "Mając", "Zakładając, że"],
+    when: &["Jeżeli", "Jeśli", "Gdy", "Kiedy"],
+    then: &["Wtedy"],
+    and: &["Oraz", "I"],
+    but: &["Ale"],
+};
+
+pub static RUSSIAN: Language = Language {
+    code: "ru",
+    name: "Russian",
+    feature: &["Функция", "Функциональность", "Функционал", "Свойство"],
+    background: &["Предыстория", "Контекст"],
+    scenario: &["Сценарий", "Пример"],
+    scenario_outline: &["Структура сценария", "Шаблон сценария"],
+    examples: &["Примеры"],
+    given: &["Допустим", "Дано", "Пусть"],
+    when: &["Когда", "Если"],
+    then: &["То", "Затем", "Тогда"],
+    and: &["И", "К тому же", "Также"],
+    but: &["Но", "А", "Иначе"],
+};
+
+pub static LANGUAGES: &[&Language] = &[
+    &ENGLISH,
+    &GERMAN,
+    &LITHUANIAN,
+    &FRENCH,
+    &SPANISH,
+    &DUTCH,
+    &POLISH,
+    &RUSSIAN,
+];
+
+impl Language {
+    pub fn from_code(code: &str) -> Option<&'static Language> {
+        LANGUAGES
+            .iter()
+            .find(|language| language.code == code)
+            .copied()
+    }
+
+    /// Language named by a `# language: xx` header before the first non comment line of a feature file
+    pub fn from_header(source: &str) -> Option<&'static Language> {
+        for line in source.lines().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            let comment = line.strip_prefix('#')?.trim();
+            if let Some(code) = comment.strip_prefix("language:") {
+                return Self::from_code(code.trim());
+            }
+        }
+        None
+    }
+
+    /// Keyword used when printing a step
+    pub fn keyword(&self, keyword: Keyword) -> &'static str {
+        let keywords = match keyword {
+            Keyword::Given => self.given,
+            Keyword::When => self.when,
+            Keyword::Then => self.then,
+            Keyword::And => self.and,
+            Keyword::But => self.but,
+        };
+        keywords[0]
+    }
+}
@@ -0,0 +1,31 @@
+//! Feature file parser, step expressions, keyword tables and snippets shared by `gnerkinf`
+//! and `gnerkinf-macros`, use them through the `gnerkinf` crate
+
+use core::fmt::Display;
+
+pub mod expression;
+pub mod gherkin;
+pub mod language;
+pub mod snippet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Keyword {
+    Given,
+    When,
+    Then,
+    And,
+    But,
+}
+
+impl Display for Keyword {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let keyword = match self {
+            Keyword::Given => "Given",
+            Keyword::When => "When",
+            Keyword::Then => "Then",
+            Keyword::And => "And",
+            Keyword::But => "But",
+        };
+        f.write_str(keyword)
+    }
+}
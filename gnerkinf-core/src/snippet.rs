@@ -0,0 +1,182 @@
+use crate::Keyword;
+
+/// Code printed for undefined steps, see `StepRegistry::snippets` of `gnerkinf`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SnippetStyle {
+    /// `.given("the account balance is ${int}", |world: &World, int: i64| { todo!() })`
+    #[default]
+    Registry,
+    /// `#[given("the account balance is ${int}")]` step function of a `#[feature]` module
+    StepFunction,
+    /// `.when("I press the coffee button", |state| { todo!() })` of the chained contexts
+    Chaining,
+}
+
+/// Definition inferred from the text of an undefined step
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snippet {
+    keyword: Keyword,
+    kind: Keyword,
+    text: String,
+    /// Cucumber Expression with `{int}`, `{float}` and `{string}` for the values found in the text
+    expression: String,
+    /// Parameter names, each with its Rust type
+    parameters: Vec<(&'static str, &'static str)>,
+}
+
+impl Snippet {
+    /// `kind` is the keyword resolved for `And` and `But`
+    pub fn new(keyword: Keyword, kind: Keyword, text: &str) -> Self {
+        let mut expression = String::new();
+        let mut parameters = Vec::new();
+        let chars: Vec<char> = text.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let after_word = i > 0 && chars[i - 1].is_alphanumeric();
+            if (c == '"' || c == '\'') && !after_word {
+                if let Some(end) = chars[i + 1..].iter().position(|&other| other == c) {
+                    expression.push_str("{string}");
+                    parameters.push(("string", "String"));
+                    i += end + 2;
+                    continue;
+                }
+            }
+            if let Some(end) = number_end(&chars, i).filter(|_| !after_word) {
+                let number = &chars[i..end];
+                if number.contains(&'.') {
+                    expression.push_str("{float}");
+                    parameters.push(("float", "f64"));
+                } else {
+                    expression.push_str("{int}");
+                    parameters.push(("int", "i64"));
+                }
+                i = end;
+                continue;
+            }
+            if matches!(c, '(' | ')' | '{' | '}' | '/' | '\\') {
+                expression.push('\\');
+            }
+            expression.push(c);
+            i += 1;
+        }
+        Self {
+            keyword,
+            kind,
+            text: text.to_string(),
+            expression,
+            parameters,
+        }
+    }
+
+    pub fn code(&self, style: SnippetStyle) -> String {
+        match style {
+            SnippetStyle::Registry => format!(
+                ".{}({:?}, |world: &World{}| {{\n    todo!()\n}})",
+                method(self.kind),
+                self.expression,
+                self.arguments()
+                    .iter()
+                    .map(|argument| format!(", {argument}"))
+                    .collect::<String>()
+            ),
+            SnippetStyle::StepFunction => {
+                let mut arguments = vec!["world: &World".to_string()];
+                arguments.extend(self.arguments());
+                format!(
+                    "#[{}({:?})]\nfn {}({}) {{\n    todo!()\n}}",
+                    method(self.kind),
+                    self.expression,
+                    self.function_name(),
+                    arguments.join(", ")
+                )
+            }
+            SnippetStyle::Chaining => match self.keyword {
+                Keyword::Given => format!(
+                    "given({:?}, || {{\n    todo!()\n}}, create_stdout_writer())",
+                    self.text
+                ),
+                keyword => format!(
+                    ".{}({:?}, |state| {{\n    todo!()\n}})",
+                    method(keyword),
+                    self.text
+                ),
+            },
+        }
+    }
+
+    /// `name: Type` of every parameter, numbering repeated names
+    fn arguments(&self) -> Vec<String> {
+        self.parameters
+            .iter()
+            .enumerate()
+            .map(|(index, (name, ty))| {
+                let repeated = self.parameters[..index]
+                    .iter()
+                    .filter(|(other, _)| other == name)
+                    .count();
+                if repeated == 0 {
+                    format!("{name}: {ty}")
+                } else {
+                    format!("{name}{}: {ty}", repeated + 1)
+                }
+            })
+            .collect()
+    }
+
+    /// Snake case of the words of the expression
+    fn function_name(&self) -> String {
+        let mut name = String::new();
+        let mut parameter = false;
+        for c in self.expression.chars() {
+            match c {
+                '{' => parameter = true,
+                '}' => parameter = false,
+                _ if parameter => (),
+                c if c.is_alphanumeric() => name.extend(c.to_lowercase()),
+                _ if !name.is_empty() && !name.ends_with('_') => name.push('_'),
+                _ => (),
+            }
+        }
+        let name = name.trim_end_matches('_');
+        if name.starts_with(|c: char| c.is_alphabetic()) {
+            name.to_string()
+        } else {
+            format!("step_{name}")
+        }
+    }
+}
+
+fn method(keyword: Keyword) -> &'static str {
+    match keyword {
+        Keyword::Given => "given",
+        Keyword::When => "when",
+        Keyword::Then => "then",
+        Keyword::And | Keyword::But => "and",
+    }
+}
+
+/// End of the number starting at `start`, if it's followed by neither a letter nor a digit
+fn number_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut end = start;
+    if chars.get(end) == Some(&'-') {
+        end += 1;
+    }
+    let digits = end;
+    while chars.get(end).is_some_and(char::is_ascii_digit) {
+        end += 1;
+    }
+    if end == digits {
+        return None;
+    }
+    if chars.get(end) == Some(&'.') && chars.get(end + 1).is_some_and(char::is_ascii_digit) {
+        end += 1;
+        while chars.get(end).is_some_and(char::is_ascii_digit) {
+            end += 1;
+        }
+    }
+    match chars.get(end) {
+        Some(c) if c.is_alphanumeric() => None,
+        _ => Some(end),
+    }
+}
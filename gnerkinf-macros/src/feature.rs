@@ -0,0 +1,186 @@
+use std::{collections::HashSet, env, fs, path::PathBuf};
+
+use gnerkinf_core::{
+    gherkin::{self, Case, Step},
+    snippet::{Snippet, SnippetStyle},
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Item, ItemMod, LitStr};
+
+use crate::step::{step_fns, StepFn};
+
+pub(crate) fn expand(path: LitStr, mut module: ItemMod) -> syn::Result<TokenStream> {
+    let Some((_, items)) = module.content.as_mut() else {
+        return Err(syn::Error::new_spanned(
+            &module,
+            "#[feature] needs an inline module with the step functions",
+        ));
+    };
+    let file = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join(path.value());
+    let source = fs::read_to_string(&file).map_err(|error| {
+        syn::Error::new(
+            path.span(),
+            format!("can't read {}: {error}", file.display()),
+        )
+    })?;
+    let feature = gherkin::parse(&source).map_err(|error| {
+        syn::Error::new(
+            path.span(),
+            format!("{}:{}: {}", path.value(), error.line, error.message),
+        )
+    })?;
+
+    let mut step_functions = Vec::new();
+    for item in items.iter_mut() {
+        if let Item::Fn(function) = item {
+            step_functions.extend(step_fns(function)?);
+        }
+    }
+
+    let mut errors: Option<syn::Error> = None;
+    let mut tests = Vec::new();
+    let mut names = HashSet::new();
+    for case in feature.cases() {
+        match test(&case, &step_functions, &path) {
+            Ok(body) => {
+                let name = unique_name(&case.name, &mut names);
+                let ignore = case.ignored.then(|| quote!(#[ignore]));
+                tests.push(quote! {
+                    #[test]
+                    #ignore
+                    fn #name() #body
+                });
+            }
+            Err(error) => match errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => errors = error.into(),
+            },
+        }
+    }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    let file = file.display().to_string();
+    items.push(syn::parse_quote! {
+        // rebuild when the feature file changes
+        const _: &str = include_str!(#file);
+    });
+    items.push(syn::parse_quote! {
+        mod scenarios {
+            #(#tests)*
+        }
+    });
+    Ok(quote!(#module))
+}
+
+fn test(case: &Case, step_functions: &[StepFn], path: &LitStr) -> syn::Result<TokenStream> {
+    let mut errors: Option<syn::Error> = None;
+    let mut calls = Vec::new();
+    let mut is_async = false;
+    for step in &case.steps {
+        let found: Vec<(&StepFn, Vec<String>)> = step_functions
+            .iter()
+            .filter(|function| function.matches(step.kind))
+            .filter_map(|function| Some((function, function.captures(&step.text)?)))
+            .collect();
+        let keyword = step.keyword;
+        let message = match found.as_slice() {
+            [(function, captures)] => {
+                is_async |= function.is_async;
+                calls.push(call(step, function, captures));
+                continue;
+            }
+            [] => format!(
+                "{}:{}: no step function matches `{keyword} {}`, it could be\n\n{}",
+                path.value(),
+                step.line,
+                step.text,
+                Snippet::new(step.keyword, step.kind, &step.text).code(SnippetStyle::StepFunction)
+            ),
+            ambiguous => format!(
+                "{}:{}: `{keyword} {}` matches several step functions: {}",
+                path.value(),
+                step.line,
+                step.text,
+                ambiguous
+                    .iter()
+                    .map(|(function, _)| format!("`{}` ({:?})", function.ident, function.pattern))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let error = syn::Error::new(path.span(), message);
+        match errors.as_mut() {
+            Some(errors) => errors.combine(error),
+            None => errors = error.into(),
+        }
+    }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    let title = &case.title;
+    let steps = quote! {
+        let mut scenario = ::gnerkinf::__private::FeatureScenario::new(
+            #title,
+            ::gnerkinf::create_stdout_writer(),
+        );
+        #(#calls)*
+    };
+    Ok(if is_async {
+        quote!({ ::gnerkinf::__private::block_on(async { #steps }) })
+    } else {
+        quote!({ #steps })
+    })
+}
+
+fn call(step: &Step, function: &StepFn, captures: &[String]) -> TokenStream {
+    let keyword = format_ident!("{}", step.keyword.to_string());
+    let text = &step.text;
+    let ident = &function.ident;
+    let world_mut = &function.world_mut;
+    let world = function.world.then(|| quote!(&#world_mut world,));
+    let arguments = captures
+        .iter()
+        .map(|capture| quote!(::gnerkinf::__private::parse_arg(#text, #capture)));
+    if function.is_async {
+        quote! {
+            scenario
+                .stepf(::gnerkinf::Keyword::#keyword, #text, |#world_mut world| async move {
+                    super::#ident(#world #(#arguments),*).await
+                })
+                .await;
+        }
+    } else {
+        quote! {
+            scenario.step(::gnerkinf::Keyword::#keyword, #text, |#world_mut world| {
+                super::#ident(#world #(#arguments),*)
+            });
+        }
+    }
+}
+
+/// Snake case test name, numbered if another scenario has the same name
+fn unique_name(name: &str, names: &mut HashSet<String>) -> syn::Ident {
+    let mut snake = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            snake.push(c.to_ascii_lowercase());
+        } else if !snake.is_empty() && !snake.ends_with('_') {
+            snake.push('_');
+        }
+    }
+    let mut snake = snake.trim_end_matches('_').to_string();
+    if !snake.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        snake.insert_str(0, "scenario_");
+    }
+    let mut unique = snake.clone();
+    let mut counter = 1;
+    while !names.insert(unique.clone()) {
+        counter += 1;
+        unique = format!("{snake}_{counter}");
+    }
+    format_ident!("{unique}")
+}
@@ -0,0 +1,42 @@
+//! Procedural macros of `gnerkinf`, use them through the `gnerkinf` crate
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, ItemMod, LitStr};
+
+mod feature;
+mod step;
+
+/// Generates a `#[test]` for every scenario and outline example of a feature file.
+/// See the `gnerkinf` crate for the step functions it expects.
+#[proc_macro_attribute]
+pub fn feature(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(attr as LitStr);
+    let module = parse_macro_input!(item as ItemMod);
+    feature::expand(path, module)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Marks a Given step function of a [`macro@feature`] module
+#[proc_macro_attribute]
+pub fn given(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// Marks a When step function of a [`macro@feature`] module
+#[proc_macro_attribute]
+pub fn when(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// Marks a Then step function of a [`macro@feature`] module
+#[proc_macro_attribute]
+pub fn then(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// Marks a step function of a [`macro@feature`] module matching steps of any kind
+#[proc_macro_attribute]
+pub fn step(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
@@ -0,0 +1,129 @@
+use gnerkinf_core::{
+    expression::{builtin_parameters, Expression},
+    Keyword,
+};
+use syn::{spanned::Spanned, Attribute, FnArg, Ident, ItemFn, LitStr, PatType, Token, Type};
+
+/// Function of the annotated module marked with `#[given]`, `#[when]`, `#[then]` or `#[step]`
+pub(crate) struct StepFn {
+    /// `None` for `#[step]`, which matches steps of any kind
+    pub(crate) kind: Option<Keyword>,
+    pub(crate) pattern: String,
+    expression: Expression,
+    pub(crate) ident: Ident,
+    /// Takes the scenario world as first argument
+    pub(crate) world: bool,
+    /// `mut` of a `&mut World` first argument
+    pub(crate) world_mut: Option<Token![mut]>,
+    pub(crate) is_async: bool,
+}
+
+impl StepFn {
+    /// Values of the parameters if `text` matches the pattern
+    pub(crate) fn captures(&self, text: &str) -> Option<Vec<String>> {
+        self.expression.captures(text)
+    }
+
+    pub(crate) fn matches(&self, kind: Keyword) -> bool {
+        self.kind.is_none_or(|own| own == kind)
+    }
+}
+
+/// A [`StepFn`] for each step attribute of `function`, removing the `#[world]` attribute of its first argument
+pub(crate) fn step_fns(function: &mut ItemFn) -> syn::Result<Vec<StepFn>> {
+    let attributes: Vec<_> = function
+        .attrs
+        .iter()
+        .filter_map(|attribute| Some((step_kind(attribute)?, attribute)))
+        .collect();
+    if attributes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (world, world_mut) = match function.sig.inputs.first_mut() {
+        Some(FnArg::Typed(argument)) => world_argument(argument)?,
+        Some(FnArg::Receiver(receiver)) => {
+            return Err(syn::Error::new(
+                receiver.span(),
+                "step functions can't take self",
+            ))
+        }
+        None => (false, None),
+    };
+    let placeholders = function.sig.inputs.len() - usize::from(world);
+
+    attributes
+        .into_iter()
+        .map(|(kind, attribute)| {
+            let pattern: LitStr = attribute.parse_args()?;
+            let expression = Expression::compile(&pattern.value(), &builtin_parameters())
+                .map_err(|error| syn::Error::new(pattern.span(), error))?;
+            if expression.parameter_count() != placeholders {
+                return Err(syn::Error::new(
+                    pattern.span(),
+                    format!(
+                        "pattern has {} parameters but `{}` takes {placeholders} step arguments",
+                        expression.parameter_count(),
+                        function.sig.ident
+                    ),
+                ));
+            }
+            Ok(StepFn {
+                kind,
+                pattern: pattern.value(),
+                expression,
+                ident: function.sig.ident.clone(),
+                world,
+                world_mut,
+                is_async: function.sig.asyncness.is_some(),
+            })
+        })
+        .collect()
+}
+
+/// Whether `argument` takes the scenario world: a `&World` or `&mut World`,
+/// or a reference to an alias of it marked with `#[world]`
+fn world_argument(argument: &mut PatType) -> syn::Result<(bool, Option<Token![mut]>)> {
+    let marked = argument
+        .attrs
+        .iter()
+        .position(|attribute| attribute.path().is_ident("world"));
+    if let Some(index) = marked {
+        argument.attrs.remove(index);
+    }
+    let Type::Reference(reference) = &*argument.ty else {
+        return match marked {
+            Some(_) => Err(syn::Error::new(
+                argument.ty.span(),
+                "the #[world] argument must be a `&World` or `&mut World`",
+            )),
+            None => Ok((false, None)),
+        };
+    };
+    let named_world = match &*reference.elem {
+        Type::Path(path) => {
+            path.qself.is_none()
+                && path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "World")
+        }
+        _ => false,
+    };
+    if marked.is_some() || named_world {
+        Ok((true, reference.mutability))
+    } else {
+        Ok((false, None))
+    }
+}
+
+fn step_kind(attribute: &Attribute) -> Option<Option<Keyword>> {
+    let ident = &attribute.path().segments.last()?.ident;
+    match ident.to_string().as_str() {
+        "given" => Some(Keyword::Given.into()),
+        "when" => Some(Keyword::When.into()),
+        "then" => Some(Keyword::Then.into()),
+        "step" => Some(None),
+        _ => None,
+    }
+}
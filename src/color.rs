@@ -0,0 +1,92 @@
+use core::fmt::Display;
+use std::{
+    env,
+    io::{stdout, IsTerminal},
+};
+
+use crate::Status;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Colour when stdout is a terminal, honouring `NO_COLOR` and `CLICOLOR_FORCE`
+    Auto,
+    Always,
+    #[default]
+    Never,
+}
+
+impl ColorChoice {
+    pub(crate) fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => detect_color(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+// https://no-color.org and https://bixense.com/clicolors
+fn detect_color() -> bool {
+    if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    if env::var_os("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+        return true;
+    }
+    stdout().is_terminal()
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const NORMAL_INTENSITY: &str = "\x1b[22m";
+const UNDERLINE: &str = "\x1b[4m";
+const NO_UNDERLINE: &str = "\x1b[24m";
+pub(crate) const MAGENTA: &str = "\x1b[35m";
+
+pub(crate) fn status_color(status: Status) -> &'static str {
+    match status {
+        Status::Passed => "\x1b[32m",
+        Status::Failed => "\x1b[31m",
+        Status::Skipped => "\x1b[36m",
+        Status::Pending => "\x1b[33m",
+    }
+}
+
+/// Wraps `text` in the given colour, restoring the default afterwards
+pub(crate) fn paint(enabled: bool, color: &str, text: impl Display) -> String {
+    let text = text.to_string();
+    if enabled && !text.is_empty() {
+        format!("{color}{text}{RESET}")
+    } else {
+        text
+    }
+}
+
+pub(crate) fn bold(enabled: bool, text: impl Display) -> String {
+    if enabled {
+        format!("{BOLD}{text}{NORMAL_INTENSITY}")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Underlines outline placeholders such as `<coffees>` without resetting the surrounding colour
+pub(crate) fn highlight_placeholders(enabled: bool, text: &str) -> String {
+    if !enabled {
+        return text.to_string();
+    }
+    let mut highlighted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>').map(|end| start + end) else {
+            break;
+        };
+        highlighted.push_str(&rest[..start]);
+        highlighted.push_str(UNDERLINE);
+        highlighted.push_str(&rest[start..=end]);
+        highlighted.push_str(NO_UNDERLINE);
+        rest = &rest[end + 1..];
+    }
+    highlighted.push_str(rest);
+    highlighted
+}
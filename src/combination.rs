@@ -0,0 +1,177 @@
+use core::fmt::{Debug, Display};
+use std::collections::HashSet;
+
+use crate::{outline, Examples, IntoReporter, OutlineContext};
+
+/// Independent value lists, such as `(vec![0, 1], vec![10, 100])`, combined into outline rows
+/// by [`Examples::product`] and [`Examples::pairwise`]
+pub trait Dimensions {
+    type Row;
+
+    /// Number of values of each list
+    fn lens(&self) -> Vec<usize>;
+
+    /// Row with the value at `indices[n]` of the n-th list
+    fn row(&self, indices: &[usize]) -> Self::Row;
+
+    /// Debug output of each value of the row, printed in its example header
+    fn cells(&self, indices: &[usize]) -> Vec<String>;
+}
+
+macro_rules! impl_dimensions {
+    ($($value:ident $index:tt),*) => {
+        impl<$($value: Clone + Debug),*> Dimensions for ($(Vec<$value>,)*) {
+            type Row = ($($value,)*);
+
+            fn lens(&self) -> Vec<usize> {
+                vec![$(self.$index.len()),*]
+            }
+
+            fn row(&self, indices: &[usize]) -> Self::Row {
+                ($(self.$index[indices[$index]].clone(),)*)
+            }
+
+            fn cells(&self, indices: &[usize]) -> Vec<String> {
+                vec![$(format!("{:?}", self.$index[indices[$index]])),*]
+            }
+        }
+    };
+}
+
+impl_dimensions!(A 0, B 1);
+impl_dimensions!(A 0, B 1, C 2);
+impl_dimensions!(A 0, B 1, C 2, D 3);
+impl_dimensions!(A 0, B 1, C 2, D 3, E 4);
+
+impl<T> Examples<T> {
+    /// Examples with a row for every combination of the values of `dimensions`, the last list varying fastest.
+    /// Each row is printed with an `Example #n` header naming its values after the `<placeholders>` of the outline.
+    pub fn product<D: Dimensions<Row = T>>(name: impl Display, dimensions: D) -> Self {
+        let combinations = product(&dimensions.lens());
+        Self::combinations(name, &dimensions, combinations)
+    }
+
+    /// Like [`Self::product`], but only with enough rows for every pair of values of two lists
+    /// to come up together once, which is far fewer rows when there are more than two lists
+    pub fn pairwise<D: Dimensions<Row = T>>(name: impl Display, dimensions: D) -> Self {
+        let combinations = pairwise(&dimensions.lens());
+        Self::combinations(name, &dimensions, combinations)
+    }
+
+    fn combinations<D: Dimensions<Row = T>>(
+        name: impl Display,
+        dimensions: &D,
+        combinations: Vec<Vec<usize>>,
+    ) -> Self {
+        let rows = combinations
+            .iter()
+            .map(|indices| dimensions.row(indices))
+            .collect();
+        let mut examples = Examples::new(name, rows);
+        examples.cells = combinations
+            .iter()
+            .map(|indices| dimensions.cells(indices))
+            .collect();
+        examples
+    }
+}
+
+/// Outline with a row for every combination of the values of `dimensions`, see [`Examples::product`]
+pub fn outline_product<D: Dimensions>(
+    s: impl Display,
+    dimensions: D,
+    w: impl IntoReporter,
+) -> OutlineContext<D::Row> {
+    outline(s, Vec::new(), w).examples(Examples::product("", dimensions))
+}
+
+/// Outline covering every pair of values of `dimensions`, see [`Examples::pairwise`]
+pub fn outline_pairwise<D: Dimensions>(
+    s: impl Display,
+    dimensions: D,
+    w: impl IntoReporter,
+) -> OutlineContext<D::Row> {
+    outline(s, Vec::new(), w).examples(Examples::pairwise("", dimensions))
+}
+
+/// Indices of every combination, the last one varying fastest
+fn product(lens: &[usize]) -> Vec<Vec<usize>> {
+    if lens.contains(&0) {
+        return Vec::new();
+    }
+    let mut combinations = Vec::new();
+    let mut indices = vec![0; lens.len()];
+    loop {
+        combinations.push(indices.clone());
+        let Some(position) = (0..lens.len()).rev().find(|&i| indices[i] + 1 < lens[i]) else {
+            return combinations;
+        };
+        indices[position] += 1;
+        indices[position + 1..].fill(0);
+    }
+}
+
+/// Combinations covering every pair of values, built one list at a time (IPOG):
+/// each row gets the value of the next list covering the most pairs not covered yet,
+/// then rows are added for the pairs still missing
+fn pairwise(lens: &[usize]) -> Vec<Vec<usize>> {
+    if lens.len() < 3 || lens.contains(&0) {
+        return product(lens);
+    }
+    // `None` is a value not needed by any pair yet, set once all lists are added
+    let mut rows: Vec<Vec<Option<usize>>> = product(&lens[..2])
+        .into_iter()
+        .map(|indices| indices.into_iter().map(Some).collect())
+        .collect();
+    for (list, &len) in lens.iter().enumerate().skip(2) {
+        let mut uncovered: HashSet<(usize, usize, usize)> = (0..list)
+            .flat_map(|other| (0..lens[other]).map(move |value| (other, value)))
+            .flat_map(|(other, value)| (0..len).map(move |index| (other, value, index)))
+            .collect();
+        for row in &mut rows {
+            let best = (0..len)
+                .max_by_key(|&index| {
+                    let covered = pairs(row, index)
+                        .filter(|pair| uncovered.contains(pair))
+                        .count();
+                    // the first index wins ties
+                    (covered, core::cmp::Reverse(index))
+                })
+                .expect("lists aren't empty");
+            for pair in pairs(row, best) {
+                uncovered.remove(&pair);
+            }
+            row.push(Some(best));
+        }
+
+        let mut missing: Vec<_> = uncovered.into_iter().collect();
+        missing.sort_unstable();
+        for (other, value, index) in missing {
+            let free = rows
+                .iter_mut()
+                .find(|row| row[list] == Some(index) && row[other].is_none());
+            if let Some(row) = free {
+                row[other] = Some(value);
+            } else {
+                let mut row = vec![None; list + 1];
+                row[other] = Some(value);
+                row[list] = Some(index);
+                rows.push(row);
+            }
+        }
+    }
+    let mut combinations: Vec<Vec<usize>> = rows
+        .into_iter()
+        .map(|row| row.into_iter().map(Option::unwrap_or_default).collect())
+        .collect();
+    combinations.sort();
+    combinations.dedup();
+    combinations
+}
+
+/// Pairs of the values of `row` with `index` in the next list
+fn pairs(row: &[Option<usize>], index: usize) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+    row.iter()
+        .enumerate()
+        .filter_map(move |(other, value)| Some((other, (*value)?, index)))
+}
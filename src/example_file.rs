@@ -0,0 +1,296 @@
+use core::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+use std::{fs, path::Path};
+
+use crate::{outline, Examples, IntoReporter, OutlineContext};
+
+/// Row of an examples file, its cells by column name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExampleRow {
+    columns: Vec<String>,
+    cells: Vec<String>,
+}
+
+impl ExampleRow {
+    /// Text of the cell in `column`
+    pub fn text(&self, column: &str) -> Result<&str, String> {
+        let index = self
+            .columns
+            .iter()
+            .position(|own| own == column)
+            .ok_or_else(|| format!("no column `{column}`"))?;
+        Ok(self.cells.get(index).map_or("", String::as_str))
+    }
+
+    /// Cell in `column` parsed with `FromStr`
+    pub fn get<T>(&self, column: &str) -> Result<T, String>
+    where
+        T: FromStr,
+        T::Err: Debug,
+    {
+        let text = self.text(column)?;
+        text.parse()
+            .map_err(|error| format!("can't parse `{text}` of column `{column}`: {error:?}"))
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+}
+
+/// Conversion of an examples file row into the value of an outline row
+pub trait FromRow: Sized {
+    fn from_row(row: &ExampleRow) -> Result<Self, String>;
+}
+
+impl FromRow for ExampleRow {
+    fn from_row(row: &ExampleRow) -> Result<Self, String> {
+        Ok(row.clone())
+    }
+}
+
+/// Outline with the rows of a CSV file, or of a JSON or TOML file with the `json` or `toml` feature.
+/// See [`Examples::from_file`].
+pub fn outline_file<T: FromRow>(
+    s: impl Display,
+    path: impl AsRef<Path>,
+    w: impl IntoReporter,
+) -> OutlineContext<T> {
+    outline(s, Vec::new(), w).examples(Examples::from_file(path))
+}
+
+impl<T: FromRow> Examples<T> {
+    /// Examples named after the file, with a row per CSV record after the header line,
+    /// per object of a JSON array or per `[[examples]]` table of a TOML file.
+    /// Failed rows are reported with the file and line they come from.
+    /// Panics if the file can't be read or one of its rows can't be converted.
+    pub fn from_file(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .unwrap_or_else(|error| panic!("can't read {}: {error}", path.display()));
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        let rows = match extension {
+            Some("csv") => csv_rows(&source),
+            Some("json") => json_rows(&source),
+            Some("toml") => toml_rows(&source),
+            _ => Err((0, "examples files must be .csv, .json or .toml".to_string())),
+        }
+        .unwrap_or_else(|(line, error)| panic!("{}:{line}: {error}", path.display()));
+
+        let name = path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let mut lines = Vec::new();
+        let values = rows
+            .into_iter()
+            .map(|(line, row)| {
+                lines.push(line);
+                T::from_row(&row)
+                    .unwrap_or_else(|error| panic!("{}:{line}: {error}", path.display()))
+            })
+            .collect();
+        let mut examples = Examples::new(name, values);
+        examples.file = Some((path.display().to_string(), lines));
+        examples
+    }
+}
+
+type Rows = Result<Vec<(usize, ExampleRow)>, (usize, String)>;
+
+/// Records of RFC 4180 CSV with the line each starts on, quoted cells may contain commas, quotes and newlines.
+/// Spaces around unquoted cells and around the quotes of quoted cells are ignored,
+/// quoted cells are kept verbatim and nothing else may follow their closing quote.
+fn csv_rows(source: &str) -> Rows {
+    let mut records: Vec<(usize, Vec<String>)> = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let start = line;
+        let mut cells = Vec::new();
+        let mut cell = String::new();
+        let mut quoted = false;
+        let mut was_quoted = false;
+        let mut push_cell = |cell: &mut String, was_quoted: &mut bool| {
+            let cell = core::mem::take(cell);
+            if core::mem::take(was_quoted) {
+                cells.push(cell);
+            } else {
+                cells.push(cell.trim().to_string());
+            }
+        };
+        loop {
+            let Some(c) = chars.next() else {
+                if quoted {
+                    return Err((start, "unterminated quoted cell".into()));
+                }
+                break;
+            };
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                '"' if quoted => quoted = false,
+                '"' if !was_quoted && cell.trim().is_empty() => {
+                    cell.clear();
+                    quoted = true;
+                    was_quoted = true;
+                }
+                ' ' | '\t' if was_quoted && !quoted => (),
+                ',' if !quoted => push_cell(&mut cell, &mut was_quoted),
+                '\r' if !quoted && chars.peek() == Some(&'\n') => (),
+                '\n' if !quoted => {
+                    line += 1;
+                    break;
+                }
+                _ if was_quoted && !quoted => {
+                    return Err((start, "unexpected text after closing quote".into()));
+                }
+                c => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    cell.push(c);
+                }
+            }
+        }
+        push_cell(&mut cell, &mut was_quoted);
+        if cells.len() > 1 || !cells[0].is_empty() {
+            records.push((start, cells));
+        }
+    }
+
+    let mut records = records.into_iter();
+    let Some((_, columns)) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let columns: Vec<String> = columns
+        .iter()
+        .map(|column| column.trim().to_string())
+        .collect();
+    records
+        .map(|(line, cells)| {
+            if cells.len() != columns.len() {
+                return Err((
+                    line,
+                    format!(
+                        "expected {} cells like the header, found {}",
+                        columns.len(),
+                        cells.len()
+                    ),
+                ));
+            }
+            let columns = columns.clone();
+            Ok((line, ExampleRow { columns, cells }))
+        })
+        .collect()
+}
+
+#[cfg(feature = "json")]
+fn json_rows(source: &str) -> Rows {
+    use serde_json::{Map, Value};
+
+    let objects: Vec<Map<String, Value>> = serde_json::from_str(source).map_err(|error| {
+        (
+            error.line(),
+            format!("expected an array of objects: {error}"),
+        )
+    })?;
+    let lines = json_object_lines(source);
+    Ok(objects
+        .into_iter()
+        .zip(lines)
+        .map(|(object, line)| {
+            let (columns, cells) = object
+                .into_iter()
+                .map(|(column, value)| {
+                    let cell = match value {
+                        Value::String(text) => text,
+                        Value::Null => String::new(),
+                        value => value.to_string(),
+                    };
+                    (column, cell)
+                })
+                .unzip();
+            (line, ExampleRow { columns, cells })
+        })
+        .collect())
+}
+
+#[cfg(not(feature = "json"))]
+fn json_rows(_source: &str) -> Rows {
+    Err((0, "reading JSON examples needs the `json` feature".into()))
+}
+
+/// Line of each object directly inside the top level array
+#[cfg(feature = "json")]
+fn json_object_lines(source: &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let mut line = 1;
+    let mut depth = 0;
+    let mut string = false;
+    let mut escaped = false;
+    for c in source.chars() {
+        match c {
+            '\n' => line += 1,
+            _ if escaped => escaped = false,
+            '\\' if string => escaped = true,
+            '"' => string = !string,
+            _ if string => (),
+            '[' | '{' => {
+                if depth == 1 && c == '{' {
+                    lines.push(line);
+                }
+                depth += 1;
+            }
+            ']' | '}' => depth -= 1,
+            _ => (),
+        }
+    }
+    lines
+}
+
+#[cfg(feature = "toml")]
+fn toml_rows(source: &str) -> Rows {
+    use std::collections::HashMap;
+
+    use toml::{Spanned, Table, Value};
+
+    let line_of = |offset: usize| source[..offset].matches('\n').count() + 1;
+    let mut document: HashMap<String, Vec<Spanned<Table>>> =
+        toml::from_str(source).map_err(|error| {
+            let line = error.span().map_or(0, |span| line_of(span.start));
+            (
+                line,
+                format!("expected `[[examples]]` tables: {}", error.message()),
+            )
+        })?;
+    let tables = document
+        .remove("examples")
+        .ok_or((0, "expected `[[examples]]` tables".to_string()))?;
+    Ok(tables
+        .into_iter()
+        .map(|table| {
+            let line = line_of(table.span().start);
+            let (columns, cells) = table
+                .into_inner()
+                .into_iter()
+                .map(|(column, value)| {
+                    let cell = match value {
+                        Value::String(text) => text,
+                        value => value.to_string(),
+                    };
+                    (column, cell)
+                })
+                .unzip();
+            (line, ExampleRow { columns, cells })
+        })
+        .collect())
+}
+
+#[cfg(not(feature = "toml"))]
+fn toml_rows(_source: &str) -> Rows {
+    Err((0, "reading TOML examples needs the `toml` feature".into()))
+}
@@ -0,0 +1,136 @@
+use core::fmt::Display;
+
+use crate::{Keyword, Language, ENGLISH};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeywordCase {
+    /// `Given`, `Scenario Outline`
+    #[default]
+    Title,
+    /// `GIVEN`, `SCENARIO OUTLINE`
+    Upper,
+    /// `given`, `scenario outline`
+    Lower,
+}
+
+/// Layout of the text written by [`crate::TextReporter`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Format {
+    pub(crate) indent: usize,
+    pub(crate) separator: Option<String>,
+    pub(crate) keyword_case: KeywordCase,
+    pub(crate) numbered: bool,
+    pub(crate) scenario_header: bool,
+    pub(crate) language: &'static Language,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            indent: 0,
+            separator: Some("=========================".to_string()),
+            keyword_case: KeywordCase::default(),
+            numbered: false,
+            scenario_header: false,
+            language: &ENGLISH,
+        }
+    }
+}
+
+impl Format {
+    /// Layout of the Gherkin pretty formatter: indented steps under a `Scenario:` header and no separator
+    pub fn pretty() -> Self {
+        Self {
+            indent: 4,
+            separator: None,
+            keyword_case: KeywordCase::Title,
+            numbered: false,
+            scenario_header: true,
+            language: &ENGLISH,
+        }
+    }
+
+    /// Number of spaces in front of each step
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Line written after each scenario, `None` to write nothing
+    pub fn separator(mut self, separator: Option<impl Display>) -> Self {
+        self.separator = separator.map(|s| s.to_string());
+        self
+    }
+
+    pub fn keyword_case(mut self, keyword_case: KeywordCase) -> Self {
+        self.keyword_case = keyword_case;
+        self
+    }
+
+    /// Prefix steps with their position in the scenario
+    pub fn numbered(mut self, numbered: bool) -> Self {
+        self.numbered = numbered;
+        self
+    }
+
+    /// Write a `Scenario:` line with the scenario title before the first step, see [`crate::titled`]
+    pub fn scenario_header(mut self, scenario_header: bool) -> Self {
+        self.scenario_header = scenario_header;
+        self
+    }
+
+    /// Language of the printed keywords
+    pub fn language(mut self, language: &'static Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    pub(crate) fn keyword(&self, keyword: Keyword) -> String {
+        self.case(self.language.keyword(keyword))
+    }
+
+    pub(crate) fn scenario_keyword(&self) -> String {
+        self.case(self.language.scenario[0])
+    }
+
+    pub(crate) fn outline_keyword(&self) -> String {
+        self.case(self.language.scenario_outline[0])
+    }
+
+    pub(crate) fn examples_keyword(&self) -> String {
+        self.case(self.language.examples[0])
+    }
+
+    /// `Example`, the second `Scenario` keyword of the language
+    pub(crate) fn example_keyword(&self) -> String {
+        let scenario = self.language.scenario;
+        self.case(scenario.get(1).unwrap_or(&scenario[0]))
+    }
+
+    fn case(&self, keyword: &str) -> String {
+        match self.keyword_case {
+            KeywordCase::Title => keyword.to_string(),
+            KeywordCase::Upper => keyword.to_uppercase(),
+            KeywordCase::Lower => keyword.to_lowercase(),
+        }
+    }
+
+    /// Headers sit half way between the margin and the steps
+    pub(crate) fn header_indent(&self) -> String {
+        " ".repeat(self.indent / 2)
+    }
+
+    pub(crate) fn step_indent(&self) -> String {
+        " ".repeat(self.indent)
+    }
+
+    /// `Example #n` sections sit under the `Scenario Outline:` header
+    pub(crate) fn example_indent(&self) -> String {
+        " ".repeat(self.indent / 2 + 2)
+    }
+
+    /// Steps of an `Example #n` section, as far under its header as steps under a `Scenario:` header
+    pub(crate) fn example_step_indent(&self) -> String {
+        " ".repeat(self.indent / 2 + 2 + (self.indent - self.indent / 2).max(2))
+    }
+}
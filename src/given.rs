@@ -1,31 +1,28 @@
-use core::{fmt::Display, future::Future, pin::Pin};
-use std::io::Write;
+use core::{fmt::Display, future::Future, marker::PhantomData, pin::Pin};
 
-use crate::{WhenContext, WhenContextFuture};
+use crate::{scenario::Scenario, IntoReporter, Keyword, Strict, WhenContext, WhenContextFuture};
 
-pub fn given<T, F>(s: impl Display, callback: F, mut w: impl Write + 'static) -> GivenContext<T>
+pub fn given<T, F>(s: impl Display, callback: F, w: impl IntoReporter) -> GivenContext<T>
 where
     F: FnOnce() -> T,
 {
-    writeln!(w, "Given {}", s).expect("writing failed");
+    let mut scenario = Scenario::new(w);
+    let value = scenario.step(Keyword::Given, s, callback);
     GivenContext {
-        writer: Box::new(w),
-        value: callback(),
+        scenario,
+        value,
+        strictness: PhantomData,
     }
 }
 
-pub fn given_data<T>(s: impl Display, value: T, mut w: impl Write + 'static) -> GivenContext<T> {
-    writeln!(w, "Given {}", s).expect("writing failed");
-    GivenContext {
-        writer: Box::new(w),
-        value,
-    }
+pub fn given_data<T>(s: impl Display, value: T, w: impl IntoReporter) -> GivenContext<T> {
+    given(s, || value, w)
 }
 
 pub fn givenf<EndType, Callback, Fut>(
     s: impl Display + 'static,
     callback: Callback,
-    w: impl Write + 'static,
+    w: impl IntoReporter + 'static,
 ) -> GivenContextFuture<EndType>
 where
     Callback: FnOnce() -> Fut,
@@ -41,7 +38,7 @@ where
 pub fn given_dataf<T>(
     s: impl Display + 'static,
     value: T,
-    w: impl Write + 'static,
+    w: impl IntoReporter + 'static,
 ) -> GivenContextFuture<T>
 where
     T: 'static,
@@ -52,19 +49,21 @@ where
     }
 }
 
-pub struct GivenContext<T> {
-    writer: Box<dyn Write>,
-    value: T,
+pub struct GivenContext<T, S = Strict> {
+    pub(crate) scenario: Scenario,
+    pub(crate) value: T,
+    pub(crate) strictness: PhantomData<S>,
 }
 
-impl<T> GivenContext<T> {
-    pub fn when<Closure, EndType>(self, s: impl Display, fut: Closure) -> WhenContext<EndType>
+impl<T, S> GivenContext<T, S> {
+    pub fn when<Closure, EndType>(self, s: impl Display, fut: Closure) -> WhenContext<EndType, S>
     where
         Closure: FnOnce(T) -> EndType,
     {
         WhenContext {
-            writer: self.writer,
+            scenario: self.scenario,
             value: self.value,
+            strictness: PhantomData,
         }
         .when(s, fut)
     }
@@ -73,27 +72,33 @@ impl<T> GivenContext<T> {
         self,
         s: impl Display,
         fut: Closure,
-    ) -> WhenContext<EndType>
+    ) -> WhenContext<EndType, S>
     where
         Closure: FnOnce(T) -> Fut,
         Fut: Future<Output = EndType>,
     {
         WhenContext {
-            writer: self.writer,
+            scenario: self.scenario,
             value: self.value,
+            strictness: PhantomData,
         }
         .whenf(s, fut)
         .await
     }
 
-    pub fn and<Closure, EndType>(mut self, s: impl Display, fut: Closure) -> GivenContext<EndType>
+    pub fn and<Closure, EndType>(
+        mut self,
+        s: impl Display,
+        fut: Closure,
+    ) -> GivenContext<EndType, S>
     where
         Closure: FnOnce(T) -> EndType,
     {
-        writeln!(self.writer, "And {}", s).expect("writing failed");
+        let value = self.scenario.step(Keyword::And, s, || fut(self.value));
         GivenContext {
-            writer: self.writer,
-            value: fut(self.value),
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
         }
     }
 
@@ -101,26 +106,30 @@ impl<T> GivenContext<T> {
         mut self,
         s: impl Display,
         fut: Closure,
-    ) -> GivenContext<EndType>
+    ) -> GivenContext<EndType, S>
     where
         Closure: FnOnce(T) -> Fut,
         Fut: Future<Output = EndType>,
     {
-        writeln!(self.writer, "And {}", s).expect("writing failed");
+        let value = self
+            .scenario
+            .stepf(Keyword::And, s, || fut(self.value))
+            .await;
         GivenContext {
-            writer: self.writer,
-            value: fut(self.value).await,
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
         }
     }
 }
 
 #[must_use = "Context must be awaited"]
-pub struct GivenContextFuture<T> {
-    context: Pin<Box<dyn Future<Output = GivenContext<T>>>>,
+pub struct GivenContextFuture<T, S = Strict> {
+    pub(crate) context: Pin<Box<dyn Future<Output = GivenContext<T, S>>>>,
 }
 
-impl<T> Future for GivenContextFuture<T> {
-    type Output = GivenContext<T>;
+impl<T, S> Future for GivenContextFuture<T, S> {
+    type Output = GivenContext<T, S>;
 
     fn poll(
         mut self: Pin<&mut Self>,
@@ -130,12 +139,12 @@ impl<T> Future for GivenContextFuture<T> {
     }
 }
 
-impl<T> GivenContextFuture<T> {
+impl<T, S: 'static> GivenContextFuture<T, S> {
     pub fn andf<Closure, Fut, EndType>(
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> GivenContextFuture<EndType>
+    ) -> GivenContextFuture<EndType, S>
     where
         T: 'static,
         EndType: 'static,
@@ -145,8 +154,9 @@ impl<T> GivenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             GivenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .andf(s, fut)
             .await
@@ -161,7 +171,7 @@ impl<T> GivenContextFuture<T> {
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> GivenContextFuture<EndType>
+    ) -> GivenContextFuture<EndType, S>
     where
         T: 'static,
         Closure: FnOnce(T) -> EndType + 'static,
@@ -169,8 +179,9 @@ impl<T> GivenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             GivenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .and(s, fut)
         };
@@ -184,7 +195,7 @@ impl<T> GivenContextFuture<T> {
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> WhenContextFuture<EndType>
+    ) -> WhenContextFuture<EndType, S>
     where
         T: 'static,
         Closure: FnOnce(T) -> EndType + 'static,
@@ -192,8 +203,9 @@ impl<T> GivenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             WhenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .when(s, fut)
         };
@@ -206,7 +218,7 @@ impl<T> GivenContextFuture<T> {
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> WhenContextFuture<EndType>
+    ) -> WhenContextFuture<EndType, S>
     where
         T: 'static,
         EndType: 'static,
@@ -216,8 +228,9 @@ impl<T> GivenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             WhenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .whenf(s, fut)
             .await
@@ -232,11 +245,13 @@ impl<T> GivenContextFuture<T> {
 async fn given_future<EndType>(
     s: impl Display,
     fut: impl Future<Output = EndType>,
-    mut w: impl Write + 'static,
+    w: impl IntoReporter,
 ) -> GivenContext<EndType> {
-    writeln!(w, "Given {}", s).expect("writing failed");
+    let mut scenario = Scenario::new(w);
+    let value = scenario.stepf(Keyword::Given, s, || fut).await;
     GivenContext {
-        writer: Box::new(w),
-        value: fut.await,
+        scenario,
+        value,
+        strictness: PhantomData,
     }
 }
@@ -1,11 +1,42 @@
+mod color;
+mod combination;
+mod example_file;
+mod format;
 mod given;
 mod logger;
+mod macros;
 mod outcome;
+mod panic;
+mod property;
+mod registry;
+mod reporter;
+mod retry;
+mod scenario;
+mod steps;
+mod strictness;
 mod then;
+mod timeout;
 mod when;
+mod world;
 
+pub use color::ColorChoice;
+pub use combination::*;
+pub use example_file::*;
+pub use format::*;
 pub use given::*;
+pub use gnerkinf_core::{expression::Parameter, language::*, snippet::SnippetStyle};
+pub use gnerkinf_macros::{feature, given, step, then, when};
 pub use logger::*;
+pub use macros::__private;
 pub use outcome::*;
+pub use panic::*;
+pub use property::*;
+pub use registry::*;
+pub use reporter::*;
+pub use retry::*;
+pub use steps::*;
+pub use strictness::*;
 pub use then::*;
+pub use timeout::*;
 pub use when::*;
+pub use world::*;
@@ -1,28 +1,90 @@
-use std::io::{stdout, StdoutLock, Write};
+use std::{
+    io::{Error, Write},
+    panic::catch_unwind,
+};
 
+use crate::{panic::panic_message, ColorChoice, TextReporter};
+
+/// Buffers the scenario output and prints it in one go when the scenario ends,
+/// so that parallel tests do not interleave and libtest can capture it.
+/// A failed print is a write error of the reporter, handled by its [`crate::WriteErrorPolicy`].
 pub fn create_stdout_writer() -> impl Write {
-    SeperatorLogger {
-        writer: stdout().lock(),
-    }
+    SeperatorLogger { buffer: Vec::new() }
+}
+
+/// Prints the scenario only when it fails, keeping passing scenarios quiet
+pub fn create_failure_writer() -> FailureWriter<impl Write> {
+    FailureWriter::new(create_stdout_writer())
+}
+
+/// Stdout reporter coloured when stdout is a terminal
+pub fn create_stdout_reporter() -> TextReporter<impl Write> {
+    TextReporter::new(create_stdout_writer()).color(ColorChoice::Auto)
 }
 
 struct SeperatorLogger {
-    writer: StdoutLock<'static>,
+    buffer: Vec<u8>,
 }
 
 impl Drop for SeperatorLogger {
     fn drop(&mut self) {
-        writeln!(self.writer, "=========================").expect("failed to write separator");
-        self.writer.flush().expect("failed to flush")
+        // only left over when used without a reporter, which would have flushed it
+        let _ = self.flush();
     }
 }
 
 impl Write for SeperatorLogger {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.writer.write(buf)
+        self.buffer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let buffer = core::mem::take(&mut self.buffer);
+        print(&buffer)
+    }
+}
+
+/// `print!`, which goes through the test harness output capture unlike a stdout handle,
+/// with the panic of a failed print, such as on a closed stdout, turned into an error
+fn print(buffer: &[u8]) -> std::io::Result<()> {
+    catch_unwind(|| print!("{}", String::from_utf8_lossy(buffer)))
+        .map_err(|payload| Error::other(panic_message(payload.as_ref())))
+}
+
+/// Keeps everything written to it and passes it on to `writer` only if the scenario panics
+pub struct FailureWriter<W: Write> {
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> FailureWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write> Drop for FailureWriter<W> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            // already unwinding, a failed write must not abort the process
+            let _ = self.writer.write_all(&self.buffer);
+            let _ = self.writer.flush();
+        }
+    }
+}
+
+impl<W: Write> Write for FailureWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.write(buf)
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
-        self.writer.flush()
+        Ok(())
     }
 }
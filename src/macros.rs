@@ -0,0 +1,288 @@
+/// Writes a scenario in Gherkin like syntax, expanding to the [`crate::given`], `when` and `then` calls.
+///
+/// Every step is a keyword, its text and the step closure. The text is passed to `format!`,
+/// so it can refer to variables in scope. Closures written as `async |..| ..` run as async steps.
+///
+/// ```ignore
+/// scenario! {
+///     fn buy_coffee() {
+///         Given "there are {coffees} coffees left" => || Machine::new(coffees);
+///         When "I press the coffee button" => |mut machine| machine.make_coffee();
+///         Then "I should be served a coffee" => async |coffee| assert!(coffee.is_some());
+///     }
+/// }
+/// ```
+///
+/// The function becomes a `#[test]` running the steps, async ones on a minimal executor.
+/// An expression in the parentheses replaces the stdout reporter, the scenario is titled after the function.
+/// `async fn` scenarios are left to the runtime named by their own attribute, e.g. `#[tokio::test]`.
+/// Without `fn`, `scenario!(reporter, Given ...)` runs the steps in place and returns the last context.
+/// A misspelled keyword or a step out of order is reported at its keyword.
+#[macro_export]
+macro_rules! scenario {
+    ($(#[$attr:meta])* async fn $name:ident($($w:expr)?) { $($steps:tt)* }) => {
+        $(#[$attr])*
+        async fn $name() {
+            $crate::scenario!(
+                @steps $crate::__private::Start($crate::scenario!(@reporter $name $($w)?)); $($steps)*
+            );
+        }
+    };
+    ($(#[$attr:meta])* fn $name:ident($($w:expr)?) { $($steps:tt)* }) => {
+        #[test]
+        $(#[$attr])*
+        fn $name() {
+            $crate::__private::block_on(async {
+                $crate::scenario!(
+                    @steps $crate::__private::Start($crate::scenario!(@reporter $name $($w)?)); $($steps)*
+                );
+            });
+        }
+    };
+    (@reporter $name:ident) => {
+        $crate::titled(stringify!($name), $crate::create_stdout_writer())
+    };
+    (@reporter $name:ident $w:expr) => {
+        $crate::titled(stringify!($name), $w)
+    };
+    (@steps $context:expr;) => {
+        $context
+    };
+    (@steps $context:expr; $keyword:ident $text:literal => async || $body:expr $(; $($rest:tt)*)?) => {
+        $crate::scenario!(
+            @steps $crate::__private::Async($context)
+                .$keyword(format!($text), || async move { $body })
+                .await;
+            $($($rest)*)?
+        )
+    };
+    (@steps $context:expr; $keyword:ident $text:literal => async |$($arg:pat_param),*| $body:expr $(; $($rest:tt)*)?) => {
+        $crate::scenario!(
+            @steps $crate::__private::Async($context)
+                .$keyword(format!($text), |$($arg),*| async move { $body })
+                .await;
+            $($($rest)*)?
+        )
+    };
+    (@steps $context:expr; $keyword:ident $text:literal => $closure:expr $(; $($rest:tt)*)?) => {
+        $crate::scenario!(
+            @steps $crate::__private::Sync($context).$keyword(format!($text), $closure);
+            $($($rest)*)?
+        )
+    };
+    ($w:expr, $($steps:tt)*) => {
+        $crate::__private::block_on(async {
+            $crate::scenario!(@steps $crate::__private::Start($w); $($steps)*)
+        })
+    };
+}
+
+/// Support for [`scenario!`], not part of the public API
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub mod __private {
+    use core::{
+        fmt::{Debug, Display},
+        future::Future,
+        marker::PhantomData,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+    use std::{
+        str::FromStr,
+        sync::Arc,
+        task::Wake,
+        thread::{self, Thread},
+    };
+
+    use crate::{
+        scenario::Scenario, GivenContext, IntoReporter, Keyword, ThenContext, WhenContext, World,
+    };
+
+    /// Reporter of a scenario before its first step
+    pub struct Start<W>(pub W);
+
+    /// Runs the next step synchronously
+    pub struct Sync<C>(pub C);
+
+    /// Runs the next step as a future
+    pub struct Async<C>(pub C);
+
+    impl<W: IntoReporter> Sync<Start<W>> {
+        pub fn Given<T>(self, s: impl Display, callback: impl FnOnce() -> T) -> GivenContext<T> {
+            crate::given(s, callback, self.0 .0)
+        }
+    }
+
+    impl<W: IntoReporter> Async<Start<W>> {
+        pub async fn Given<Fut: Future>(
+            self,
+            s: impl Display,
+            callback: impl FnOnce() -> Fut,
+        ) -> GivenContext<Fut::Output> {
+            let mut scenario = Scenario::new(self.0 .0);
+            let value = scenario.stepf(Keyword::Given, s, callback).await;
+            GivenContext {
+                scenario,
+                value,
+                strictness: PhantomData,
+            }
+        }
+    }
+
+    impl<T> Sync<GivenContext<T>> {
+        pub fn And<E>(self, s: impl Display, fut: impl FnOnce(T) -> E) -> GivenContext<E> {
+            self.0.and(s, fut)
+        }
+
+        pub fn When<E>(self, s: impl Display, fut: impl FnOnce(T) -> E) -> WhenContext<E> {
+            self.0.when(s, fut)
+        }
+    }
+
+    impl<T> Async<GivenContext<T>> {
+        pub async fn And<Fut: Future>(
+            self,
+            s: impl Display,
+            fut: impl FnOnce(T) -> Fut,
+        ) -> GivenContext<Fut::Output> {
+            self.0.andf(s, fut).await
+        }
+
+        pub async fn When<Fut: Future>(
+            self,
+            s: impl Display,
+            fut: impl FnOnce(T) -> Fut,
+        ) -> WhenContext<Fut::Output> {
+            self.0.whenf(s, fut).await
+        }
+    }
+
+    impl<T> Sync<WhenContext<T>> {
+        pub fn When<E>(self, s: impl Display, fut: impl FnOnce(T) -> E) -> WhenContext<E> {
+            self.0.when(s, fut)
+        }
+
+        pub fn And<E>(self, s: impl Display, fut: impl FnOnce(T) -> E) -> WhenContext<E> {
+            self.0.and(s, fut)
+        }
+
+        pub fn Then<E>(self, s: impl Display, fut: impl FnOnce(T) -> E) -> ThenContext<E> {
+            self.0.then(s, fut)
+        }
+    }
+
+    impl<T> Async<WhenContext<T>> {
+        pub async fn When<Fut: Future>(
+            self,
+            s: impl Display,
+            fut: impl FnOnce(T) -> Fut,
+        ) -> WhenContext<Fut::Output> {
+            self.0.whenf(s, fut).await
+        }
+
+        pub async fn And<Fut: Future>(
+            self,
+            s: impl Display,
+            fut: impl FnOnce(T) -> Fut,
+        ) -> WhenContext<Fut::Output> {
+            self.0.andf(s, fut).await
+        }
+
+        pub async fn Then<Fut: Future>(
+            self,
+            s: impl Display,
+            fut: impl FnOnce(T) -> Fut,
+        ) -> ThenContext<Fut::Output> {
+            self.0.thenf(s, fut).await
+        }
+    }
+
+    impl<T> Sync<ThenContext<T>> {
+        pub fn Then<E>(self, s: impl Display, fut: impl FnOnce(T) -> E) -> ThenContext<E> {
+            self.0.then(s, fut)
+        }
+
+        pub fn And<E>(self, s: impl Display, fut: impl FnOnce(T) -> E) -> ThenContext<E> {
+            self.0.and(s, fut)
+        }
+    }
+
+    impl<T> Async<ThenContext<T>> {
+        pub async fn Then<Fut: Future>(
+            self,
+            s: impl Display,
+            fut: impl FnOnce(T) -> Fut,
+        ) -> ThenContext<Fut::Output> {
+            self.0.thenf(s, fut).await
+        }
+
+        pub async fn And<Fut: Future>(
+            self,
+            s: impl Display,
+            fut: impl FnOnce(T) -> Fut,
+        ) -> ThenContext<Fut::Output> {
+            self.0.andf(s, fut).await
+        }
+    }
+
+    /// Scenario of a feature file, see [`crate::feature`]
+    pub struct FeatureScenario {
+        scenario: Scenario,
+    }
+
+    impl FeatureScenario {
+        pub fn new(name: &str, w: impl IntoReporter) -> Self {
+            Self {
+                scenario: Scenario::named(name, w),
+            }
+        }
+
+        pub fn step<R>(&mut self, keyword: Keyword, text: &str, step: impl FnOnce(World) -> R) {
+            let world = self.scenario.world();
+            self.scenario.step(keyword, text, || drop(step(world)));
+        }
+
+        pub async fn stepf<Fut: Future>(
+            &mut self,
+            keyword: Keyword,
+            text: &str,
+            step: impl FnOnce(World) -> Fut,
+        ) {
+            let world = self.scenario.world();
+            self.scenario.stepf(keyword, text, || step(world)).await;
+        }
+    }
+
+    /// Parses the text captured by a step placeholder into the type of the step function argument
+    pub fn parse_arg<T>(text: &str, capture: &str) -> T
+    where
+        T: FromStr,
+        T::Err: Debug,
+    {
+        capture
+            .parse()
+            .unwrap_or_else(|error| panic!("can't parse {capture:?} of step {text:?}: {error:?}"))
+    }
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Polls `future` on the current thread, parking it while the future is pending
+    pub fn block_on<Fut: Future>(future: Fut) -> Fut::Output {
+        let mut future = pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(value) = future.as_mut().poll(&mut context) {
+                return value;
+            }
+            thread::park();
+        }
+    }
+}
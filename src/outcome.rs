@@ -1,45 +1,305 @@
-use core::{fmt::Display, future::Future};
-use std::io::Write;
+use core::{any::Any, cell::RefCell, fmt::Display, future::Future};
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    rc::Rc,
+};
+
+use crate::{
+    panic::{panic_message, CatchUnwind},
+    property::placeholders,
+    IntoReporter, Keyword, Reporter, StepReport,
+};
 
 pub struct OutlineContext<T> {
-    writer: Box<dyn Write>,
-    value: Vec<T>,
+    reporter: Rc<RefCell<Box<dyn Reporter>>>,
+    title: String,
+    /// Whether every row gets an `Example #n` header, see [`Self::run`]
+    sections: bool,
+    groups: Vec<Examples<T>>,
+    only_tagged: Vec<String>,
+    skip_tagged: Vec<String>,
+}
+
+/// Named group of outline rows, like a Gherkin `Examples:` section
+pub struct Examples<T> {
+    name: String,
+    tags: Vec<String>,
+    rows: Vec<T>,
+    /// Path and line of every row read from a file
+    pub(crate) file: Option<(String, Vec<usize>)>,
+    /// Cells of every row of a combination, printed in its header
+    pub(crate) cells: Vec<Vec<String>>,
 }
 
-pub fn outline<T>(
-    s: impl Display,
-    value: Vec<T>,
-    mut w: impl Write + 'static,
-) -> OutlineContext<T> {
-    writeln!(w, "Scenario outline {}", s).expect("writing failed");
+impl<T> Examples<T> {
+    pub fn new(name: impl Display, rows: Vec<T>) -> Self {
+        Self {
+            name: name.to_string(),
+            tags: Vec::new(),
+            rows,
+            file: None,
+            cells: Vec::new(),
+        }
+    }
+
+    /// Tag such as `@slow` for [`OutlineContext::only_tagged`] and [`OutlineContext::skip_tagged`]
+    pub fn tag(mut self, tag: impl Display) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// `coffees = 0, deposit = 100` for a row of a combination, named after the `<placeholders>` of `title`
+    fn header(&self, title: &str, index: usize) -> Option<String> {
+        let cells = self.cells.get(index)?;
+        let mut columns = placeholders(title);
+        if columns.len() != cells.len() {
+            columns = (1..=cells.len()).map(|n| format!("value {n}")).collect();
+        }
+        let values: Vec<String> = columns
+            .iter()
+            .zip(cells)
+            .map(|(column, cell)| format!("{column} = {cell}"))
+            .collect();
+        Some(values.join(", "))
+    }
+
+    /// `examples "valid deposits" #2: message`, `example #2: message` for the rows passed to [`outline`]
+    /// or `path:line: message` for rows read from a file.
+    /// The header of a combination follows the number, as in `example #2 (coffees = 0): message`.
+    fn failure(&self, header: Option<String>, index: usize, payload: &(dyn Any + Send)) -> String {
+        let message = panic_message(payload);
+        let header = header.map_or_else(String::new, |header| format!(" ({header})"));
+        if let Some((path, lines)) = &self.file {
+            format!("{path}:{}: {message}", lines[index])
+        } else if self.name.is_empty() {
+            format!("example #{}{header}: {message}", index + 1)
+        } else {
+            format!(
+                "examples \"{}\" #{}{header}: {message}",
+                self.name,
+                index + 1
+            )
+        }
+    }
+}
+
+pub fn outline<T>(s: impl Display, value: Vec<T>, w: impl IntoReporter) -> OutlineContext<T> {
+    let title = s.to_string();
+    let mut reporter = w.into_reporter();
+    reporter.outline(&title);
     OutlineContext {
-        writer: Box::new(w),
-        value,
+        reporter: Rc::new(RefCell::new(reporter)),
+        title,
+        sections: false,
+        groups: vec![Examples::new("", value)],
+        only_tagged: Vec::new(),
+        skip_tagged: Vec::new(),
     }
 }
 
 impl<T> OutlineContext<T> {
-    pub fn map<Closure>(mut self, fut: Closure)
+    /// Adds a named group of rows, run after the rows passed to [`outline`] and the groups before it
+    pub fn examples(mut self, examples: Examples<T>) -> Self {
+        self.groups.push(examples);
+        self
+    }
+
+    /// Only runs the groups with `tag`, or with any of the tags if called several times
+    pub fn only_tagged(mut self, tag: impl Display) -> Self {
+        self.only_tagged.push(tag.to_string());
+        self
+    }
+
+    /// Doesn't run the groups with `tag`
+    pub fn skip_tagged(mut self, tag: impl Display) -> Self {
+        self.skip_tagged.push(tag.to_string());
+        self
+    }
+
+    /// Runs `fut` with every row and its index in its group.
+    /// Failed rows don't stop the others, the outline fails at the end naming each of them.
+    pub fn map<Closure>(self, fut: Closure)
     where
         Closure: Fn(T, usize),
     {
-        writeln!(self.writer, "=========================").expect("writing failed");
-        self.value
-            .into_iter()
-            .enumerate()
-            .for_each(|(index, data)| fut(data, index));
-        self.writer.flush().expect("outcome flush");
+        self.collect(fut);
     }
 
-    pub async fn mapf<Closure, Fut>(mut self, fut: Closure)
+    /// Async [`Self::map`]
+    pub async fn mapf<Closure, Fut>(self, fut: Closure)
     where
         Closure: Fn(T, usize) -> Fut,
         Fut: Future<Output = ()>,
     {
-        writeln!(self.writer, "=========================").expect("writing failed");
-        for (index, value) in self.value.into_iter().enumerate() {
-            fut(value, index).await
+        self.collectf(fut).await;
+    }
+
+    /// [`Self::map`] returning what `fut` returned for each row, in order,
+    /// such as the value of a chain ended with [`crate::ThenContext::into_value`]
+    pub fn collect<Closure, R>(self, fut: Closure) -> Vec<R>
+    where
+        Closure: Fn(T, usize) -> R,
+    {
+        into_values(self.try_collect(fut))
+    }
+
+    /// Async [`Self::collect`]
+    pub async fn collectf<Closure, Fut, R>(self, fut: Closure) -> Vec<R>
+    where
+        Closure: Fn(T, usize) -> Fut,
+        Fut: Future<Output = R>,
+    {
+        into_values(self.try_collectf(fut).await)
+    }
+
+    /// [`Self::collect`] handing each row an [`Example`] to pass as the writer of its scenario,
+    /// so that its steps are printed in an `Example #n` section of the outline
+    pub fn run<Closure, R>(mut self, fut: Closure) -> Vec<R>
+    where
+        Closure: Fn(T, Example) -> R,
+    {
+        self.sections = true;
+        let reporter = self.reporter.clone();
+        into_values(self.try_collect(|value, index| {
+            let reporter = reporter.clone();
+            fut(value, Example { index, reporter })
+        }))
+    }
+
+    /// Async [`Self::run`]
+    pub async fn runf<Closure, Fut, R>(mut self, fut: Closure) -> Vec<R>
+    where
+        Closure: Fn(T, Example) -> Fut,
+        Fut: Future<Output = R>,
+    {
+        self.sections = true;
+        let reporter = self.reporter.clone();
+        let results = self
+            .try_collectf(|value, index| {
+                let reporter = reporter.clone();
+                fut(value, Example { index, reporter })
+            })
+            .await;
+        into_values(results)
+    }
+
+    /// [`Self::collect`] that doesn't fail the outline, a failed row gives the line
+    /// it would have in the failure summary, such as `example #2: message`
+    pub fn try_collect<Closure, R>(mut self, fut: Closure) -> Vec<Result<R, String>>
+    where
+        Closure: Fn(T, usize) -> R,
+    {
+        let mut results = Vec::new();
+        for mut group in self.selected_groups() {
+            self.report_group(&group);
+            let rows = core::mem::take(&mut group.rows);
+            for (index, data) in rows.into_iter().enumerate() {
+                let header = self.report_row(&group, index);
+                let result = catch_unwind(AssertUnwindSafe(|| fut(data, index)));
+                results
+                    .push(result.map_err(|payload| group.failure(header, index, payload.as_ref())));
+            }
         }
-        self.writer.flush().expect("outcome flush");
+        self.reporter.borrow_mut().finish();
+        results
+    }
+
+    /// Async [`Self::try_collect`]
+    pub async fn try_collectf<Closure, Fut, R>(mut self, fut: Closure) -> Vec<Result<R, String>>
+    where
+        Closure: Fn(T, usize) -> Fut,
+        Fut: Future<Output = R>,
+    {
+        let mut results = Vec::new();
+        for mut group in self.selected_groups() {
+            self.report_group(&group);
+            let rows = core::mem::take(&mut group.rows);
+            for (index, value) in rows.into_iter().enumerate() {
+                let header = self.report_row(&group, index);
+                let result = CatchUnwind::new(fut(value, index)).await;
+                results
+                    .push(result.map_err(|payload| group.failure(header, index, payload.as_ref())));
+            }
+        }
+        self.reporter.borrow_mut().finish();
+        results
+    }
+
+    fn selected_groups(&mut self) -> Vec<Examples<T>> {
+        let has_any =
+            |group: &Examples<T>, tags: &[String]| group.tags.iter().any(|tag| tags.contains(tag));
+        core::mem::take(&mut self.groups)
+            .into_iter()
+            .filter(|group| !(group.name.is_empty() && group.rows.is_empty()))
+            .filter(|group| self.only_tagged.is_empty() || has_any(group, &self.only_tagged))
+            .filter(|group| !has_any(group, &self.skip_tagged))
+            .collect()
+    }
+
+    fn report_group(&mut self, group: &Examples<T>) {
+        if !group.name.is_empty() {
+            self.reporter
+                .borrow_mut()
+                .examples(&group.name, &group.tags);
+        }
+    }
+
+    fn report_row(&mut self, group: &Examples<T>, index: usize) -> Option<String> {
+        let header = group.header(&self.title, index);
+        if self.sections || header.is_some() {
+            let title = header.as_deref().unwrap_or_default();
+            self.reporter.borrow_mut().example(index + 1, title);
+        }
+        header
+    }
+}
+
+/// Row of an outline run by [`OutlineContext::run`], the writer of its scenario.
+/// Its steps go to the reporter of the outline instead of one of their own.
+pub struct Example {
+    index: usize,
+    reporter: Rc<RefCell<Box<dyn Reporter>>>,
+}
+
+impl Example {
+    /// Index of the row in its group
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl IntoReporter for Example {
+    fn into_reporter(self) -> Box<dyn Reporter> {
+        Box::new(ExampleReporter(self.reporter))
+    }
+}
+
+/// Forwards the steps of a row to the reporter of its outline, which prints the header and separator
+struct ExampleReporter(Rc<RefCell<Box<dyn Reporter>>>);
+
+impl Reporter for ExampleReporter {
+    fn step_started(&mut self, keyword: Keyword, text: &str) {
+        self.0.borrow_mut().step_started(keyword, text);
+    }
+
+    fn step(&mut self, step: &StepReport<'_>) {
+        self.0.borrow_mut().step(step);
+    }
+}
+
+/// Values of the rows, or a panic naming each failed one
+fn into_values<R>(results: Vec<Result<R, String>>) -> Vec<R> {
+    let count = results.len();
+    let failures: Vec<&str> = results
+        .iter()
+        .filter_map(|result| result.as_ref().err().map(String::as_str))
+        .collect();
+    if !failures.is_empty() {
+        panic!(
+            "{} of {count} examples failed:\n{}",
+            failures.len(),
+            failures.join("\n")
+        );
     }
+    results.into_iter().map(Result::unwrap).collect()
 }
@@ -0,0 +1,291 @@
+use core::{
+    any::Any,
+    fmt::{Debug, Display},
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::{
+    GivenContext, GivenContextFuture, Keyword, ThenContext, ThenContextFuture, WhenContext,
+    WhenContextFuture,
+};
+
+/// Text of a panic raised with `panic!("...")` or a failed assertion
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non string panic payload".to_string()
+    }
+}
+
+/// Resolves to `Err` with the panic payload if polling `future` panics
+pub(crate) struct CatchUnwind<Fut> {
+    future: Pin<Box<Fut>>,
+}
+
+impl<Fut> CatchUnwind<Fut> {
+    pub(crate) fn new(future: Fut) -> Self {
+        Self {
+            future: Box::pin(future),
+        }
+    }
+}
+
+impl<Fut: Future> Future for CatchUnwind<Fut> {
+    type Output = std::thread::Result<Fut::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = self.future.as_mut();
+        match catch_unwind(AssertUnwindSafe(|| future.poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(value)) => Poll::Ready(Ok(value)),
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
+
+/// Panic captured by a `*_panics` step
+pub struct CaughtPanic {
+    payload: Box<dyn Any + Send>,
+}
+
+impl CaughtPanic {
+    pub(crate) fn new(payload: Box<dyn Any + Send>) -> Self {
+        Self { payload }
+    }
+
+    /// Message of a panic raised with a string payload, e.g. by `panic!("...")` or `assert!`,
+    /// or "non string panic payload"
+    pub fn message(&self) -> String {
+        panic_message(self.payload.as_ref())
+    }
+
+    /// Whether the payload is a `T`, e.g. a value passed to `std::panic::panic_any`
+    pub fn is<T: Any>(&self) -> bool {
+        self.payload.is::<T>()
+    }
+
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.payload.downcast_ref()
+    }
+
+    pub fn into_payload(self) -> Box<dyn Any + Send> {
+        self.payload
+    }
+}
+
+impl Debug for CaughtPanic {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CaughtPanic")
+            .field("message", &self.message())
+            .finish()
+    }
+}
+
+/// Fails the step unless it panicked, and with a message containing `expected` if given
+pub(crate) fn expect_panic(
+    keyword: Keyword,
+    text: &str,
+    expected: Option<&str>,
+    result: std::thread::Result<()>,
+) -> CaughtPanic {
+    let Err(payload) = result else {
+        panic!("{keyword} {text}: expected a panic");
+    };
+    let caught = CaughtPanic::new(payload);
+    if let Some(expected) = expected {
+        let message = caught.message();
+        if !message.contains(expected) {
+            panic!("{keyword} {text}: expected a panic with {expected:?}, got {message:?}");
+        }
+    }
+    caught
+}
+
+/// Steps of a context and of its future expected to panic, continuing with the caught panic.
+/// Each step is `panics, panics_with, panicsf, panics_withf: Keyword => next context, next future`.
+macro_rules! impl_panic_steps {
+    ($context:ident, $future:ident {
+        $($panics:ident, $panics_with:ident, $panicsf:ident, $panics_withf:ident:
+            $keyword:ident => $next:ident, $next_future:ident;)*
+    }) => {
+        impl<T, S> $context<T, S> {
+            $(
+                /// Step expected to panic, continuing with the caught panic
+                pub fn $panics<Closure, EndType>(
+                    mut self,
+                    s: impl Display,
+                    fut: Closure,
+                ) -> $next<CaughtPanic, S>
+                where
+                    Closure: FnOnce(T) -> EndType,
+                {
+                    let value = self
+                        .scenario
+                        .expect_panic(Keyword::$keyword, s, None, || fut(self.value));
+                    $next {
+                        scenario: self.scenario,
+                        value,
+                        strictness: PhantomData,
+                    }
+                }
+
+                /// Step expected to panic with a message containing `expected`
+                pub fn $panics_with<Closure, EndType>(
+                    mut self,
+                    s: impl Display,
+                    expected: &str,
+                    fut: Closure,
+                ) -> $next<CaughtPanic, S>
+                where
+                    Closure: FnOnce(T) -> EndType,
+                {
+                    let value = self.scenario.expect_panic(
+                        Keyword::$keyword,
+                        s,
+                        expected.into(),
+                        || fut(self.value),
+                    );
+                    $next {
+                        scenario: self.scenario,
+                        value,
+                        strictness: PhantomData,
+                    }
+                }
+
+                pub async fn $panicsf<Closure, Fut>(
+                    mut self,
+                    s: impl Display,
+                    fut: Closure,
+                ) -> $next<CaughtPanic, S>
+                where
+                    Closure: FnOnce(T) -> Fut,
+                    Fut: Future,
+                {
+                    let value = self
+                        .scenario
+                        .expect_panicf(Keyword::$keyword, s, None, || fut(self.value))
+                        .await;
+                    $next {
+                        scenario: self.scenario,
+                        value,
+                        strictness: PhantomData,
+                    }
+                }
+
+                pub async fn $panics_withf<Closure, Fut>(
+                    mut self,
+                    s: impl Display,
+                    expected: &str,
+                    fut: Closure,
+                ) -> $next<CaughtPanic, S>
+                where
+                    Closure: FnOnce(T) -> Fut,
+                    Fut: Future,
+                {
+                    let value = self
+                        .scenario
+                        .expect_panicf(Keyword::$keyword, s, expected.into(), || fut(self.value))
+                        .await;
+                    $next {
+                        scenario: self.scenario,
+                        value,
+                        strictness: PhantomData,
+                    }
+                }
+            )*
+        }
+
+        impl<T: 'static, S: 'static> $future<T, S> {
+            $(
+                /// Step expected to panic, continuing with the caught panic
+                pub fn $panics<Closure, EndType>(
+                    self,
+                    s: impl Display + 'static,
+                    fut: Closure,
+                ) -> $next_future<CaughtPanic, S>
+                where
+                    EndType: 'static,
+                    Closure: FnOnce(T) -> EndType + 'static,
+                {
+                    let context = async { self.context.await.$panics(s, fut) };
+                    $next_future {
+                        context: Box::pin(context),
+                    }
+                }
+
+                /// Step expected to panic with a message containing `expected`
+                pub fn $panics_with<Closure, EndType>(
+                    self,
+                    s: impl Display + 'static,
+                    expected: &str,
+                    fut: Closure,
+                ) -> $next_future<CaughtPanic, S>
+                where
+                    EndType: 'static,
+                    Closure: FnOnce(T) -> EndType + 'static,
+                {
+                    let expected = expected.to_string();
+                    let context = async move { self.context.await.$panics_with(s, &expected, fut) };
+                    $next_future {
+                        context: Box::pin(context),
+                    }
+                }
+
+                pub fn $panicsf<Closure, Fut>(
+                    self,
+                    s: impl Display + 'static,
+                    fut: Closure,
+                ) -> $next_future<CaughtPanic, S>
+                where
+                    Closure: FnOnce(T) -> Fut + 'static,
+                    Fut: Future + 'static,
+                {
+                    let context = async { self.context.await.$panicsf(s, fut).await };
+                    $next_future {
+                        context: Box::pin(context),
+                    }
+                }
+
+                pub fn $panics_withf<Closure, Fut>(
+                    self,
+                    s: impl Display + 'static,
+                    expected: &str,
+                    fut: Closure,
+                ) -> $next_future<CaughtPanic, S>
+                where
+                    Closure: FnOnce(T) -> Fut + 'static,
+                    Fut: Future + 'static,
+                {
+                    let expected = expected.to_string();
+                    let context = async move {
+                        self.context
+                            .await
+                            .$panics_withf(s, &expected, fut)
+                            .await
+                    };
+                    $next_future {
+                        context: Box::pin(context),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+impl_panic_steps!(GivenContext, GivenContextFuture {
+    when_panics, when_panics_with, when_panicsf, when_panics_withf: When => WhenContext, WhenContextFuture;
+});
+impl_panic_steps!(WhenContext, WhenContextFuture {
+    when_panics, when_panics_with, when_panicsf, when_panics_withf: When => WhenContext, WhenContextFuture;
+    then_panics, then_panics_with, then_panicsf, then_panics_withf: Then => ThenContext, ThenContextFuture;
+});
+impl_panic_steps!(ThenContext, ThenContextFuture {
+    then_panics, then_panics_with, then_panicsf, then_panics_withf: Then => ThenContext, ThenContextFuture;
+});
@@ -0,0 +1,467 @@
+use core::{
+    fmt::{Debug, Display},
+    future::Future,
+    ops::RangeInclusive,
+};
+use std::{
+    env,
+    panic::{catch_unwind, AssertUnwindSafe},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    panic::{panic_message, CatchUnwind},
+    IntoReporter, Reporter,
+};
+
+/// Environment variable overriding the seed of every property outline
+pub const SEED_VARIABLE: &str = "GNERKINF_SEED";
+
+/// Deterministic SplitMix64 random numbers for the generators
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Number in `0..n`, `n` must not be zero
+    pub fn below(&mut self, n: u128) -> u128 {
+        (u128::from(self.next_u64()) * n) >> 64
+    }
+}
+
+/// Strategy generating the values of a property outline and shrinking failing ones
+pub trait Generator {
+    type Value: Clone + Debug;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value;
+
+    /// Simpler values than `value`, simplest first
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value>;
+
+    /// Cells of the Gherkin examples row printed for `value`
+    fn cells(&self, value: &Self::Value) -> Vec<String> {
+        vec![format!("{value:?}")]
+    }
+}
+
+/// Integers of a range, shrinking toward zero or the bound closest to it
+pub struct Integers<T> {
+    range: RangeInclusive<T>,
+}
+
+pub fn integers<T>(range: RangeInclusive<T>) -> Integers<T> {
+    Integers { range }
+}
+
+macro_rules! impl_integers {
+    ($($int:ty),*) => {$(
+        impl Generator for Integers<$int> {
+            type Value = $int;
+
+            fn generate(&self, rng: &mut Rng) -> $int {
+                let (start, end) = (i128::from(*self.range.start()), i128::from(*self.range.end()));
+                // edge cases come up more often than a uniform pick would give them
+                let value = match rng.below(8) {
+                    0 => [start, end, 0i128.clamp(start, end)][rng.below(3) as usize],
+                    _ => start + rng.below((end - start + 1) as u128) as i128,
+                };
+                value as $int
+            }
+
+            fn shrink(&self, value: &$int) -> Vec<$int> {
+                let (start, end) = (i128::from(*self.range.start()), i128::from(*self.range.end()));
+                shrink_integer(i128::from(*value), 0i128.clamp(start, end))
+                    .into_iter()
+                    .map(|value| value as $int)
+                    .collect()
+            }
+        }
+    )*};
+}
+
+impl_integers!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl Generator for Integers<usize> {
+    type Value = usize;
+
+    fn generate(&self, rng: &mut Rng) -> usize {
+        integers(*self.range.start() as u64..=*self.range.end() as u64).generate(rng) as usize
+    }
+
+    fn shrink(&self, value: &usize) -> Vec<usize> {
+        let range = *self.range.start() as u64..=*self.range.end() as u64;
+        integers(range)
+            .shrink(&(*value as u64))
+            .into_iter()
+            .map(|value| value as usize)
+            .collect()
+    }
+}
+
+/// `target`, then values halving the distance from `value` to it
+fn shrink_integer(value: i128, target: i128) -> Vec<i128> {
+    let mut candidates = Vec::new();
+    let mut distance = value - target;
+    while distance != 0 {
+        candidates.push(value - distance);
+        distance /= 2;
+    }
+    candidates
+}
+
+/// Strings of characters from `alphabet`, shrinking to shorter strings of earlier characters
+pub struct Strings {
+    alphabet: Vec<char>,
+    len: RangeInclusive<usize>,
+}
+
+pub fn strings(alphabet: &str, len: RangeInclusive<usize>) -> Strings {
+    assert!(!alphabet.is_empty(), "strings need an alphabet");
+    Strings {
+        alphabet: alphabet.chars().collect(),
+        len,
+    }
+}
+
+impl Generator for Strings {
+    type Value = String;
+
+    fn generate(&self, rng: &mut Rng) -> String {
+        let elements = one_of(self.alphabet.clone());
+        vecs(elements, self.len.clone())
+            .generate(rng)
+            .into_iter()
+            .collect()
+    }
+
+    fn shrink(&self, value: &String) -> Vec<String> {
+        let elements = one_of(self.alphabet.clone());
+        vecs(elements, self.len.clone())
+            .shrink(&value.chars().collect())
+            .into_iter()
+            .map(|chars| chars.into_iter().collect())
+            .collect()
+    }
+}
+
+/// Vectors of generated elements, shrinking by removing elements and then shrinking each of them
+pub struct Vecs<G> {
+    elements: G,
+    len: RangeInclusive<usize>,
+}
+
+pub fn vecs<G: Generator>(elements: G, len: RangeInclusive<usize>) -> Vecs<G> {
+    Vecs { elements, len }
+}
+
+impl<G: Generator> Generator for Vecs<G> {
+    type Value = Vec<G::Value>;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value {
+        let len = integers(self.len.clone()).generate(rng);
+        (0..len).map(|_| self.elements.generate(rng)).collect()
+    }
+
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+        let mut candidates = Vec::new();
+        let min = *self.len.start();
+        // remove chunks, the largest first
+        let mut chunk = value.len().saturating_sub(min);
+        while chunk > 0 {
+            for start in (0..=value.len() - chunk).step_by(chunk) {
+                let mut smaller = value[..start].to_vec();
+                smaller.extend_from_slice(&value[start + chunk..]);
+                candidates.push(smaller);
+            }
+            chunk /= 2;
+        }
+        for (index, element) in value.iter().enumerate() {
+            for simpler in self.elements.shrink(element) {
+                let mut candidate = value.clone();
+                candidate[index] = simpler;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+/// One of `values`, shrinking to the ones before it
+pub struct OneOf<T> {
+    values: Vec<T>,
+}
+
+pub fn one_of<T: Clone + Debug + PartialEq>(values: Vec<T>) -> OneOf<T> {
+    assert!(!values.is_empty(), "one_of needs values");
+    OneOf { values }
+}
+
+impl<T: Clone + Debug + PartialEq> Generator for OneOf<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        self.values[rng.below(self.values.len() as u128) as usize].clone()
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        let index = self
+            .values
+            .iter()
+            .position(|own| own == value)
+            .unwrap_or_default();
+        self.values[..index].to_vec()
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($generator:ident $index:tt),*) => {
+        impl<$($generator: Generator),*> Generator for ($($generator,)*) {
+            type Value = ($($generator::Value,)*);
+
+            fn generate(&self, rng: &mut Rng) -> Self::Value {
+                ($(self.$index.generate(rng),)*)
+            }
+
+            fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+                let mut candidates = Vec::new();
+                $(
+                    for simpler in self.$index.shrink(&value.$index) {
+                        let mut candidate = value.clone();
+                        candidate.$index = simpler;
+                        candidates.push(candidate);
+                    }
+                )*
+                candidates
+            }
+
+            fn cells(&self, value: &Self::Value) -> Vec<String> {
+                let mut cells = Vec::new();
+                $(cells.extend(self.$index.cells(&value.$index));)*
+                cells
+            }
+        }
+    };
+}
+
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+impl_tuple!(A 0, B 1, C 2, D 3);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4);
+
+/// Outline whose rows are generated, see [`property`]
+pub struct PropertyContext<G> {
+    reporter: Box<dyn Reporter>,
+    title: String,
+    generator: G,
+    cases: usize,
+    seed: u64,
+    /// Whether the seed was set by [`PropertyContext::seed`]
+    fixed_seed: bool,
+}
+
+/// Outline running the steps with `cases` values from `generator`, 100 by default.
+/// The seed is printed with the outline title and taken from `GNERKINF_SEED` when it's set,
+/// unless the outline fixes it with [`PropertyContext::seed`].
+/// A failing value is shrunk to a minimal one, printed as a Gherkin examples row
+/// whose columns are the `<placeholders>` of `s`.
+pub fn property<G: Generator>(
+    s: impl Display,
+    generator: G,
+    w: impl IntoReporter,
+) -> PropertyContext<G> {
+    let seed = env::var(SEED_VARIABLE)
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or_else(|| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            Rng::new(now.as_nanos() as u64).next_u64()
+        });
+    PropertyContext {
+        reporter: w.into_reporter(),
+        title: s.to_string(),
+        generator,
+        cases: 100,
+        seed,
+        fixed_seed: false,
+    }
+}
+
+impl<G: Generator> PropertyContext<G> {
+    pub fn cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// Seed of the generated values, used even when `GNERKINF_SEED` is set
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.fixed_seed = true;
+        self
+    }
+
+    /// Runs `fut` with every generated value and its index.
+    /// Panics after the first failing value with the minimal failing value found by shrinking it.
+    pub fn map<Closure>(mut self, fut: Closure)
+    where
+        Closure: Fn(G::Value, usize),
+    {
+        self.start();
+        let mut rng = Rng::new(self.seed);
+        let run = |value: G::Value, index| {
+            catch_unwind(AssertUnwindSafe(|| fut(value, index)))
+                .map_err(|payload| panic_message(payload.as_ref()))
+        };
+        for index in 0..self.cases {
+            let value = self.generator.generate(&mut rng);
+            let Err(message) = run(value.clone(), index) else {
+                continue;
+            };
+            let mut shrinker = Shrinker::new(&self.generator, value, message);
+            while let Some(candidate) = shrinker.next_candidate() {
+                if let Err(message) = run(candidate.clone(), index) {
+                    shrinker.failed(candidate, message);
+                }
+            }
+            let (minimal, message) = shrinker.into_minimal();
+            self.fail(index, &minimal, &message);
+        }
+        self.reporter.finish();
+    }
+
+    /// Async [`Self::map`]
+    pub async fn mapf<Closure, Fut>(mut self, fut: Closure)
+    where
+        Closure: Fn(G::Value, usize) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        self.start();
+        let mut rng = Rng::new(self.seed);
+        for index in 0..self.cases {
+            let value = self.generator.generate(&mut rng);
+            let Err(payload) = CatchUnwind::new(fut(value.clone(), index)).await else {
+                continue;
+            };
+            let message = panic_message(payload.as_ref());
+            let mut shrinker = Shrinker::new(&self.generator, value, message);
+            while let Some(candidate) = shrinker.next_candidate() {
+                if let Err(payload) = CatchUnwind::new(fut(candidate.clone(), index)).await {
+                    shrinker.failed(candidate, panic_message(payload.as_ref()));
+                }
+            }
+            let (minimal, message) = shrinker.into_minimal();
+            self.fail(index, &minimal, &message);
+        }
+        self.reporter.finish();
+    }
+
+    fn start(&mut self) {
+        let title = format!("{} (seed {})", self.title, self.seed);
+        self.reporter.outline(&title);
+    }
+
+    fn fail(&mut self, index: usize, minimal: &G::Value, message: &str) -> ! {
+        self.reporter.finish();
+        let cells = self.generator.cells(minimal);
+        let mut columns = placeholders(&self.title);
+        if columns.len() != cells.len() {
+            columns = (1..=cells.len()).map(|n| format!("value {n}")).collect();
+        }
+        let rerun = if self.fixed_seed {
+            String::new()
+        } else {
+            format!(", rerun it with {SEED_VARIABLE}={}", self.seed)
+        };
+        panic!(
+            "property failed on case #{} with seed {}{rerun}\n\
+             minimal failing example:\n{}\n{message}",
+            index + 1,
+            self.seed,
+            examples_table(&columns, &cells),
+        );
+    }
+}
+
+/// Candidates tried while shrinking before giving up on finding a smaller failing value
+const MAX_SHRINK_STEPS: usize = 1000;
+
+/// Search for the simplest value still failing, moving to the first failing shrink candidate
+/// until none fails. The caller runs each candidate, so that sync and async outlines share it.
+struct Shrinker<'a, G: Generator> {
+    generator: &'a G,
+    value: G::Value,
+    message: String,
+    candidates: std::vec::IntoIter<G::Value>,
+    steps: usize,
+}
+
+impl<'a, G: Generator> Shrinker<'a, G> {
+    fn new(generator: &'a G, value: G::Value, message: String) -> Self {
+        Self {
+            candidates: generator.shrink(&value).into_iter(),
+            generator,
+            value,
+            message,
+            steps: 0,
+        }
+    }
+
+    fn next_candidate(&mut self) -> Option<G::Value> {
+        if self.steps >= MAX_SHRINK_STEPS {
+            return None;
+        }
+        self.steps += 1;
+        self.candidates.next()
+    }
+
+    /// Continues the search from `candidate`, which failed with `message`
+    fn failed(&mut self, candidate: G::Value, message: String) {
+        self.candidates = self.generator.shrink(&candidate).into_iter();
+        self.value = candidate;
+        self.message = message;
+    }
+
+    fn into_minimal(self) -> (G::Value, String) {
+        (self.value, self.message)
+    }
+}
+
+/// Names of the `<placeholders>` of an outline title
+pub(crate) fn placeholders(title: &str) -> Vec<String> {
+    title
+        .split('<')
+        .skip(1)
+        .filter_map(|rest| Some(rest.split_once('>')?.0.to_string()))
+        .collect()
+}
+
+fn examples_table(columns: &[String], cells: &[String]) -> String {
+    let widths: Vec<usize> = columns
+        .iter()
+        .zip(cells)
+        .map(|(column, cell)| column.chars().count().max(cell.chars().count()))
+        .collect();
+    let row = |values: &[String]| {
+        let cells: Vec<String> = values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!(" {value:width$} "))
+            .collect();
+        format!("  |{}|", cells.join("|"))
+    };
+    format!("Examples:\n{}\n{}", row(columns), row(cells))
+}
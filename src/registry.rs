@@ -0,0 +1,382 @@
+use core::{fmt::Debug, str::FromStr};
+use std::{
+    collections::HashMap,
+    fs,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+use gnerkinf_core::{
+    expression::{builtin_parameters, Expression, Parameter, ParameterType},
+    gherkin::{self, Case, Step},
+    snippet::{Snippet, SnippetStyle},
+};
+
+use crate::{
+    __private::parse_arg, create_stdout_writer, panic::panic_message, scenario::Scenario,
+    IntoReporter, Keyword, World,
+};
+
+/// Step definitions matched against the steps of feature files at runtime.
+/// Definitions are Cucumber Expressions like `the account balance is ${int}`,
+/// the text of each parameter is parsed with `FromStr` into the matching closure argument.
+pub struct StepRegistry {
+    parameters: HashMap<String, ParameterType>,
+    definitions: Vec<Definition>,
+    snippet_style: SnippetStyle,
+    snippet_file: Option<PathBuf>,
+}
+
+type Call = Box<dyn Fn(&World, &str, Vec<String>)>;
+
+struct Definition {
+    /// `None` for definitions matching steps of any kind
+    kind: Option<Keyword>,
+    /// The expression, or the regex between slashes
+    source: String,
+    expression: Expression,
+    step: Call,
+}
+
+/// Closure or function taking the scenario world and one argument per expression parameter
+pub trait StepFn<Args> {
+    fn arity(&self) -> usize;
+
+    fn call(&self, world: &World, text: &str, captures: Vec<String>);
+}
+
+macro_rules! impl_step_fn {
+    ($($arg:ident),*) => {
+        impl<F, $($arg),*> StepFn<($($arg,)*)> for F
+        where
+            F: Fn(&World, $($arg),*),
+            $($arg: FromStr, $arg::Err: Debug,)*
+        {
+            fn arity(&self) -> usize {
+                <[&str]>::len(&[$(stringify!($arg)),*])
+            }
+
+            #[allow(unused_variables, unused_mut)]
+            fn call(&self, world: &World, text: &str, captures: Vec<String>) {
+                let mut captures = captures.iter();
+                self(world, $(parse_arg::<$arg>(text, captures.next().expect("one capture per argument"))),*)
+            }
+        }
+    };
+}
+
+impl_step_fn!();
+impl_step_fn!(A);
+impl_step_fn!(A, B);
+impl_step_fn!(A, B, C);
+impl_step_fn!(A, B, C, D);
+impl_step_fn!(A, B, C, D, E);
+impl_step_fn!(A, B, C, D, E, G);
+
+impl Default for StepRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StepRegistry {
+    pub fn new() -> Self {
+        Self {
+            parameters: builtin_parameters(),
+            definitions: Vec::new(),
+            snippet_style: SnippetStyle::default(),
+            snippet_file: None,
+        }
+    }
+
+    /// Code printed for the undefined steps of a feature file, defaults to [`SnippetStyle::Registry`]
+    pub fn snippets(mut self, style: SnippetStyle) -> Self {
+        self.snippet_style = style;
+        self
+    }
+
+    /// Also writes the snippets of undefined steps to `path`, replacing its content
+    pub fn snippets_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.snippet_file = Some(path.into());
+        self
+    }
+
+    /// Makes `{P::NAME}` usable in the expressions registered after it
+    pub fn parameter<P: Parameter>(mut self) -> Self {
+        self.parameters
+            .insert(P::NAME.to_string(), ParameterType::new(P::REGEX));
+        self
+    }
+
+    pub fn given<Args>(self, expression: &str, step: impl StepFn<Args> + 'static) -> Self {
+        self.define(Keyword::Given.into(), expression, step)
+    }
+
+    pub fn when<Args>(self, expression: &str, step: impl StepFn<Args> + 'static) -> Self {
+        self.define(Keyword::When.into(), expression, step)
+    }
+
+    pub fn then<Args>(self, expression: &str, step: impl StepFn<Args> + 'static) -> Self {
+        self.define(Keyword::Then.into(), expression, step)
+    }
+
+    /// Definition matching steps of any kind
+    pub fn step<Args>(self, expression: &str, step: impl StepFn<Args> + 'static) -> Self {
+        self.define(None, expression, step)
+    }
+
+    /// Given definition matching a regex anywhere in the step text, like cucumber-ruby.
+    /// Every capture group is parsed into the next argument, groups that didn't match give an empty string.
+    pub fn given_regex<Args>(self, regex: &str, step: impl StepFn<Args> + 'static) -> Self {
+        self.define_regex(Keyword::Given.into(), regex, step)
+    }
+
+    /// When definition matching a regex, see [`Self::given_regex`]
+    pub fn when_regex<Args>(self, regex: &str, step: impl StepFn<Args> + 'static) -> Self {
+        self.define_regex(Keyword::When.into(), regex, step)
+    }
+
+    /// Then definition matching a regex, see [`Self::given_regex`]
+    pub fn then_regex<Args>(self, regex: &str, step: impl StepFn<Args> + 'static) -> Self {
+        self.define_regex(Keyword::Then.into(), regex, step)
+    }
+
+    /// Definition matching a regex in steps of any kind, see [`Self::given_regex`]
+    pub fn step_regex<Args>(self, regex: &str, step: impl StepFn<Args> + 'static) -> Self {
+        self.define_regex(None, regex, step)
+    }
+
+    /// Panics if the expression is invalid or its parameters don't match the arguments of `step`
+    fn define<Args>(
+        self,
+        kind: Option<Keyword>,
+        source: &str,
+        step: impl StepFn<Args> + 'static,
+    ) -> Self {
+        let expression = Expression::compile(source, &self.parameters)
+            .unwrap_or_else(|error| panic!("invalid step expression `{source}`: {error}"));
+        self.push(kind, source.to_string(), expression, step)
+    }
+
+    /// Panics if the regex is invalid or its capture groups don't match the arguments of `step`
+    fn define_regex<Args>(
+        self,
+        kind: Option<Keyword>,
+        regex: &str,
+        step: impl StepFn<Args> + 'static,
+    ) -> Self {
+        let expression = Expression::from_regex(regex)
+            .unwrap_or_else(|error| panic!("invalid step regex `/{regex}/`: {error}"));
+        self.push(kind, format!("/{regex}/"), expression, step)
+    }
+
+    fn push<Args>(
+        mut self,
+        kind: Option<Keyword>,
+        source: String,
+        expression: Expression,
+        step: impl StepFn<Args> + 'static,
+    ) -> Self {
+        let parameters = expression.parameter_count();
+        if parameters != step.arity() {
+            panic!(
+                "step expression `{source}` has {parameters} parameters but its step takes {} arguments",
+                step.arity()
+            );
+        }
+        self.definitions.push(Definition {
+            kind,
+            source,
+            expression,
+            step: Box::new(move |world, text, captures| step.call(world, text, captures)),
+        });
+        self
+    }
+
+    /// Runs every scenario of the feature file, failing once all of them ran if any failed.
+    /// Snippets for the undefined steps are printed before failing.
+    pub fn run_feature(&self, path: impl AsRef<Path>) {
+        self.run_feature_with(path, create_stdout_writer)
+    }
+
+    /// [`Self::run_feature`] reporting every scenario to a new reporter from `reporter`
+    pub fn run_feature_with<R: IntoReporter>(
+        &self,
+        path: impl AsRef<Path>,
+        reporter: impl Fn() -> R,
+    ) {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .unwrap_or_else(|error| panic!("can't read {}: {error}", path.display()));
+        let feature = gherkin::parse(&source)
+            .unwrap_or_else(|error| panic!("{}:{}: {}", path.display(), error.line, error.message));
+        let cases: Vec<Case> = feature
+            .cases()
+            .into_iter()
+            .filter(|case| !case.ignored)
+            .collect();
+        let snippets = self.snippets_for(&cases);
+        let failures: Vec<String> = cases
+            .iter()
+            .filter_map(|case| {
+                let (line, message) = self.run_case(case, reporter()).err()?;
+                Some(format!(
+                    "{}:{line}: {}: {message}",
+                    path.display(),
+                    case.title
+                ))
+            })
+            .collect();
+        if !snippets.is_empty() {
+            self.print_snippets(&snippets);
+        }
+        if !failures.is_empty() {
+            panic!(
+                "{} of {} scenarios failed:\n{}",
+                failures.len(),
+                cases.len(),
+                failures.join("\n")
+            );
+        }
+    }
+
+    /// Runs the steps of `case` until one fails, reporting the rest as skipped.
+    /// Fails with the line and panic message of the failed step.
+    fn run_case(&self, case: &Case, reporter: impl IntoReporter) -> Result<(), (usize, String)> {
+        let mut scenario = Scenario::named(&case.title, reporter);
+        let world = scenario.world();
+        let mut failure = None;
+        for step in &case.steps {
+            if failure.is_some() {
+                scenario.skip(step.keyword, &step.text);
+                continue;
+            }
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                scenario.step(step.keyword, &step.text, || self.execute(&world, step))
+            }));
+            if let Err(payload) = result {
+                failure = (step.line, panic_message(payload.as_ref())).into();
+            }
+        }
+        failure.map_or(Ok(()), Err)
+    }
+
+    /// A snippet for every distinct undefined step
+    fn snippets_for(&self, cases: &[Case]) -> Vec<Snippet> {
+        let mut snippets: Vec<Snippet> = Vec::new();
+        for step in cases.iter().flat_map(|case| &case.steps) {
+            if !self.matching(step.kind, &step.text).is_empty() {
+                continue;
+            }
+            let snippet = Snippet::new(step.keyword, step.kind, &step.text);
+            let code = snippet.code(self.snippet_style);
+            if !snippets
+                .iter()
+                .any(|other| other.code(self.snippet_style) == code)
+            {
+                snippets.push(snippet);
+            }
+        }
+        snippets
+    }
+
+    fn print_snippets(&self, snippets: &[Snippet]) {
+        let code = snippets
+            .iter()
+            .map(|snippet| snippet.code(self.snippet_style))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        println!("\nYou can implement the undefined steps with these snippets:\n\n{code}\n");
+        if let Some(file) = &self.snippet_file {
+            fs::write(file, format!("{code}\n")).unwrap_or_else(|error| {
+                panic!("can't write snippets to {}: {error}", file.display())
+            });
+        }
+    }
+
+    fn execute(&self, world: &World, step: &Step) {
+        match self.find(step.kind, &step.text) {
+            Ok((definition, captures)) => (definition.step)(world, &step.text, captures),
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Definitions of the step kind
+    fn candidates(&self, kind: Keyword) -> impl Iterator<Item = &Definition> {
+        self.definitions
+            .iter()
+            .filter(move |definition| definition.kind.is_none_or(|own| own == kind))
+    }
+
+    /// Every definition matching the step with its parameters
+    fn matching(&self, kind: Keyword, text: &str) -> Vec<(&Definition, Vec<String>)> {
+        self.candidates(kind)
+            .filter_map(|definition| Some((definition, definition.expression.captures(text)?)))
+            .collect()
+    }
+
+    /// The single definition matching the step and its parameters
+    fn find(&self, kind: Keyword, text: &str) -> Result<(&Definition, Vec<String>), String> {
+        let found = self.matching(kind, text);
+        match found.len() {
+            1 => Ok(found.into_iter().next().expect("one definition")),
+            0 => {
+                let mut message = format!("undefined step `{kind} {text}`");
+                let closest = self
+                    .candidates(kind)
+                    .map(|definition| (distance(text, &definition.source), definition))
+                    .min_by_key(|(distance, _)| *distance);
+                if let Some((distance, definition)) = closest {
+                    if distance <= definition.source.chars().count().max(9) / 3 {
+                        message.push_str(&format!(", did you mean `{}`?", definition.source));
+                    }
+                }
+                Err(message)
+            }
+            _ => Err(format!(
+                "ambiguous step `{kind} {text}` matches {}",
+                found
+                    .iter()
+                    .map(|(definition, _)| format!("`{}`", definition.source))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
+
+/// Edit distance of `text` to the expression `source`, whose parameters match any text for free
+fn distance(text: &str, source: &str) -> usize {
+    // `None` for a parameter
+    let mut pattern = Vec::new();
+    let mut chars = source.chars();
+    while let Some(c) = chars.next() {
+        if c == '{' {
+            chars.by_ref().find(|&c| c == '}');
+            pattern.push(None);
+        } else {
+            pattern.push(Some(c));
+        }
+    }
+    let mut previous: Vec<usize> = (0..=pattern.len()).collect();
+    for (j, token) in pattern.iter().enumerate() {
+        if token.is_none() {
+            previous[j + 1] = previous[j];
+        }
+    }
+    for (i, c) in text.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, token) in pattern.iter().enumerate() {
+            let cost = match token {
+                // the parameter matches this character too or stops before it
+                None => previous[j + 1].min(current[j]),
+                Some(own) => (previous[j] + usize::from(own != &c))
+                    .min(previous[j + 1] + 1)
+                    .min(current[j] + 1),
+            };
+            current.push(cost);
+        }
+        previous = current;
+    }
+    previous[pattern.len()]
+}
@@ -0,0 +1,386 @@
+use core::{fmt::Display, time::Duration};
+use std::io::{Error, Write};
+
+pub use gnerkinf_core::Keyword;
+
+use crate::{
+    color::{bold, highlight_placeholders, paint, status_color, ColorChoice, MAGENTA},
+    Format,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Passed,
+    Failed,
+    Skipped,
+    Pending,
+}
+
+/// Outcome of a single step, handed to a [`Reporter`] once the step closure has returned
+/// (or its future has resolved) or panicked.
+#[derive(Debug)]
+pub struct StepReport<'a> {
+    pub keyword: Keyword,
+    pub text: &'a str,
+    /// Wall-clock time spent in the step closure, including time spent awaiting it
+    pub duration: Duration,
+    /// Number of times the step closure ran, more than one for retried steps
+    pub attempts: u32,
+    pub status: Status,
+}
+
+pub trait Reporter {
+    /// Called before the first step with the title of the scenario, the one given to [`titled`]
+    /// or else the name of the running test, empty when it can't be detected
+    fn scenario(&mut self, _title: &str) {}
+
+    /// Called when a scenario outline is created
+    fn outline(&mut self, _title: &str) {}
+
+    /// Called before the rows of a named examples group of an outline
+    fn examples(&mut self, _name: &str, _tags: &[String]) {}
+
+    /// Called before an outline row printed in its own section, with its number in its group
+    /// and a title such as the values of a combination, empty if it has none
+    fn example(&mut self, _number: usize, _title: &str) {}
+
+    /// Called when a step starts, before its closure runs
+    fn step_started(&mut self, _keyword: Keyword, _text: &str) {}
+
+    fn step(&mut self, step: &StepReport<'_>);
+
+    /// Called when attempt number `attempt` of a scenario run by [`crate::retry_scenario`] failed
+    /// with `message` and the scenario is about to run again
+    fn retry(&mut self, _attempt: u32, _message: &str) {}
+
+    /// Called when a scenario run by [`crate::retry_scenario`] passed after more than one attempt
+    fn retried(&mut self, _attempts: u32) {}
+
+    /// Called once when the scenario ends
+    fn finish(&mut self) {}
+}
+
+/// Conversion used by `given*` and `outline` so that both plain writers and reporters can be passed
+pub trait IntoReporter {
+    fn into_reporter(self) -> Box<dyn Reporter>;
+}
+
+impl<W: Write + 'static> IntoReporter for W {
+    fn into_reporter(self) -> Box<dyn Reporter> {
+        Box::new(TextReporter::new(self))
+    }
+}
+
+impl IntoReporter for Box<dyn Reporter> {
+    fn into_reporter(self) -> Box<dyn Reporter> {
+        self
+    }
+}
+
+/// Reports the scenario under `title` instead of the name of the running test
+pub fn titled(title: impl Display, w: impl IntoReporter) -> Titled {
+    Titled {
+        title: title.to_string(),
+        reporter: w.into_reporter(),
+    }
+}
+
+/// Reporter with an explicit scenario title, see [`titled`]
+pub struct Titled {
+    title: String,
+    reporter: Box<dyn Reporter>,
+}
+
+impl IntoReporter for Titled {
+    fn into_reporter(self) -> Box<dyn Reporter> {
+        Box::new(self)
+    }
+}
+
+impl Reporter for Titled {
+    fn scenario(&mut self, _title: &str) {
+        self.reporter.scenario(&self.title);
+    }
+
+    fn outline(&mut self, title: &str) {
+        self.reporter.outline(title);
+    }
+
+    fn examples(&mut self, name: &str, tags: &[String]) {
+        self.reporter.examples(name, tags);
+    }
+
+    fn example(&mut self, number: usize, title: &str) {
+        self.reporter.example(number, title);
+    }
+
+    fn step_started(&mut self, keyword: Keyword, text: &str) {
+        self.reporter.step_started(keyword, text);
+    }
+
+    fn step(&mut self, step: &StepReport<'_>) {
+        self.reporter.step(step);
+    }
+
+    fn retry(&mut self, attempt: u32, message: &str) {
+        self.reporter.retry(attempt, message);
+    }
+
+    fn retried(&mut self, attempts: u32) {
+        self.reporter.retried(attempts);
+    }
+
+    fn finish(&mut self) {
+        self.reporter.finish();
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WriteErrorPolicy {
+    Ignore,
+    /// Print the first error to stderr
+    #[default]
+    Warn,
+    /// Fail the scenario unless it is already failing
+    Panic,
+}
+
+/// Keeps the first write error so that a broken writer does not fail the step being reported
+#[derive(Default)]
+struct WriteErrors {
+    policy: WriteErrorPolicy,
+    first: Option<Error>,
+    count: usize,
+}
+
+impl WriteErrors {
+    fn record(&mut self, result: std::io::Result<()>) {
+        if let Err(e) = result {
+            self.count += 1;
+            self.first.get_or_insert(e);
+        }
+    }
+
+    fn report(&mut self) {
+        let Some(error) = self.first.take() else {
+            return;
+        };
+        let message = format!(
+            "gnerkinf: {} report writes failed, first error: {error}",
+            self.count
+        );
+        self.count = 0;
+        match self.policy {
+            WriteErrorPolicy::Ignore => (),
+            WriteErrorPolicy::Panic if !std::thread::panicking() => panic!("{message}"),
+            WriteErrorPolicy::Warn | WriteErrorPolicy::Panic => eprintln!("{message}"),
+        }
+    }
+}
+
+/// Writes each step as a line of text followed by its duration
+pub struct TextReporter<W> {
+    writer: W,
+    slow_threshold: Option<Duration>,
+    color: bool,
+    format: Format,
+    step_number: usize,
+    /// Whether the steps belong to an `Example #n` section of an outline
+    example: bool,
+    errors: WriteErrors,
+}
+
+impl<W: Write> TextReporter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            slow_threshold: None,
+            color: false,
+            format: Format::default(),
+            step_number: 0,
+            example: false,
+            errors: WriteErrors::default(),
+        }
+    }
+
+    /// What to do with write errors, reported once when the scenario ends
+    pub fn on_write_error(mut self, policy: WriteErrorPolicy) -> Self {
+        self.errors.policy = policy;
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Use ANSI colours: bold keywords and one colour per step status
+    pub fn color(mut self, choice: ColorChoice) -> Self {
+        self.color = choice.enabled();
+        self
+    }
+
+    /// Steps taking at least `threshold` are marked as slow
+    pub fn slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = threshold.into();
+        self
+    }
+
+    fn is_slow(&self, duration: Duration) -> bool {
+        self.slow_threshold
+            .map(|threshold| duration >= threshold)
+            .unwrap_or_default()
+    }
+}
+
+impl<W: Write + 'static> IntoReporter for TextReporter<W> {
+    fn into_reporter(self) -> Box<dyn Reporter> {
+        Box::new(self)
+    }
+}
+
+impl<W: Write> Reporter for TextReporter<W> {
+    fn scenario(&mut self, title: &str) {
+        self.step_number = 0;
+        if self.format.scenario_header && !title.is_empty() {
+            let result = writeln!(
+                self.writer,
+                "{}{}: {title}",
+                self.format.header_indent(),
+                bold(self.color, self.format.scenario_keyword())
+            );
+            self.errors.record(result);
+        }
+    }
+
+    fn outline(&mut self, title: &str) {
+        self.example = false;
+        let result = writeln!(
+            self.writer,
+            "{}{}: {}",
+            self.format.header_indent(),
+            bold(self.color, self.format.outline_keyword()),
+            highlight_placeholders(self.color, title)
+        );
+        self.errors.record(result);
+    }
+
+    fn examples(&mut self, name: &str, tags: &[String]) {
+        self.example = false;
+        if !tags.is_empty() {
+            let result = writeln!(
+                self.writer,
+                "{}{}",
+                self.format.header_indent(),
+                tags.join(" ")
+            );
+            self.errors.record(result);
+        }
+        let result = writeln!(
+            self.writer,
+            "{}{}: {name}",
+            self.format.header_indent(),
+            bold(self.color, self.format.examples_keyword())
+        );
+        self.errors.record(result);
+    }
+
+    fn example(&mut self, number: usize, title: &str) {
+        self.step_number = 0;
+        self.example = true;
+        let title = if title.is_empty() {
+            String::new()
+        } else {
+            format!(": {title}")
+        };
+        let result = writeln!(
+            self.writer,
+            "{}{} #{number}{title}",
+            self.format.example_indent(),
+            bold(self.color, self.format.example_keyword())
+        );
+        self.errors.record(result);
+    }
+
+    fn step(&mut self, step: &StepReport<'_>) {
+        self.step_number += 1;
+        let color = status_color(step.status);
+        let attempts = if step.attempts > 1 {
+            format!(", {} attempts", step.attempts)
+        } else {
+            String::new()
+        };
+        let duration = if self.is_slow(step.duration) {
+            paint(
+                self.color,
+                MAGENTA,
+                format!("({:.2?}{attempts}, slow)", step.duration),
+            )
+        } else {
+            paint(
+                self.color,
+                color,
+                format!("({:.2?}{attempts})", step.duration),
+            )
+        };
+        let status = match step.status {
+            Status::Passed => "",
+            Status::Failed => " failed",
+            Status::Skipped => " skipped",
+            Status::Pending => " pending",
+        };
+        let number = if self.format.numbered {
+            format!("{}. ", self.step_number)
+        } else {
+            String::new()
+        };
+        let line = format!(
+            "{number}{} {}",
+            bold(self.color, self.format.keyword(step.keyword)),
+            highlight_placeholders(self.color, step.text)
+        );
+        let indent = if self.example {
+            self.format.example_step_indent()
+        } else {
+            self.format.step_indent()
+        };
+        let result = writeln!(
+            self.writer,
+            "{indent}{} {duration}{}",
+            paint(self.color, color, line),
+            paint(self.color, color, status)
+        );
+        self.errors.record(result);
+    }
+
+    fn retry(&mut self, attempt: u32, message: &str) {
+        let result = writeln!(
+            self.writer,
+            "{}{} attempt {attempt} failed, retrying: {message}",
+            self.format.header_indent(),
+            self.format.scenario_keyword()
+        );
+        self.errors.record(result);
+    }
+
+    fn retried(&mut self, attempts: u32) {
+        let result = writeln!(
+            self.writer,
+            "{}{} passed after {attempts} attempts",
+            self.format.header_indent(),
+            self.format.scenario_keyword()
+        );
+        self.errors.record(result);
+    }
+
+    fn finish(&mut self) {
+        self.example = false;
+        if let Some(separator) = &self.format.separator {
+            let result = writeln!(self.writer, "{separator}");
+            self.errors.record(result);
+        }
+        let result = self.writer.flush();
+        self.errors.record(result);
+        self.errors.report();
+    }
+}
@@ -0,0 +1,358 @@
+use core::{
+    any::Any, cell::RefCell, fmt::Display, future::Future, marker::PhantomData, time::Duration,
+};
+use std::{
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    rc::Rc,
+    thread,
+    time::Instant,
+};
+
+use crate::{
+    panic::{panic_message, CatchUnwind},
+    GivenContext, GivenContextFuture, IntoReporter, Keyword, Reporter, StepReport, ThenAfterGiven,
+    ThenContext, ThenContextFuture, ThreadTimer, Timer, WhenAfterThen, WhenContext,
+    WhenContextFuture,
+};
+
+/// Delay between attempts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backoff {
+    None,
+    Fixed(Duration),
+    /// Doubles the delay after every attempt, up to `max`
+    Exponential {
+        initial: Duration,
+        max: Duration,
+    },
+}
+
+impl Backoff {
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        match *self {
+            Backoff::None => Duration::ZERO,
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => initial
+                .checked_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+                .map(|delay| delay.min(max))
+                .unwrap_or(max),
+        }
+    }
+}
+
+/// How often a failing step is run again
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Retry {
+    attempts: Option<u32>,
+    within: Option<Duration>,
+    backoff: Backoff,
+}
+
+impl Retry {
+    /// Runs the step at most `attempts` times
+    pub fn attempts(attempts: u32, backoff: Backoff) -> Self {
+        Self {
+            attempts: attempts.into(),
+            within: None,
+            backoff,
+        }
+    }
+
+    /// Runs the step until it passes, giving up once the next attempt would start after `within`
+    pub fn eventually(within: Duration, backoff: Backoff) -> Self {
+        Self {
+            attempts: None,
+            within: within.into(),
+            backoff,
+        }
+    }
+
+    /// Delay before the next attempt or `None` if `attempt` was the last one
+    pub(crate) fn next_delay(&self, attempt: u32, elapsed: Duration) -> Option<Duration> {
+        let delay = self.backoff.delay(attempt);
+        let attempts_left = self.attempts.map(|attempts| attempt < attempts);
+        let time_left = self.within.map(|within| elapsed + delay < within);
+        match (attempts_left, time_left) {
+            (Some(false), _) | (_, Some(false)) => None,
+            _ => delay.into(),
+        }
+    }
+}
+
+/// Context whose next step is run again on failure, created by `retry` and `eventually`.
+/// Retried closures receive a clone of the step input on every attempt.
+pub struct Retrying<C> {
+    context: C,
+    retry: Retry,
+}
+
+impl<C> Retrying<C> {
+    pub(crate) fn new(context: C, retry: Retry) -> Self {
+        Self { context, retry }
+    }
+}
+
+/// `retry` and `eventually` of a context and of its future, and the steps of their [`Retrying`].
+/// Each step is `sync, async: Keyword => next context, next future`, with the strictness bound
+/// of the transition if it has one.
+macro_rules! impl_retrying_steps {
+    ($context:ident, $future:ident {
+        $($step:ident, $stepf:ident: $keyword:ident => $next:ident, $next_future:ident
+            $(where S: $transition:ident)?;)*
+    }) => {
+        impl<T, S> $context<T, S> {
+            /// Runs the next step up to `attempts` times until it passes
+            pub fn retry(self, attempts: u32, backoff: Backoff) -> Retrying<Self> {
+                Retrying::new(self, Retry::attempts(attempts, backoff))
+            }
+
+            /// Runs the next step until it passes or `within` expires
+            pub fn eventually(self, within: Duration, backoff: Backoff) -> Retrying<Self> {
+                Retrying::new(self, Retry::eventually(within, backoff))
+            }
+        }
+
+        impl<T: Clone, S> Retrying<$context<T, S>> {
+            $(
+                pub fn $step<Closure, EndType>(
+                    self,
+                    s: impl Display,
+                    fut: Closure,
+                ) -> $next<EndType, S>
+                where
+                    $(S: $transition,)?
+                    Closure: FnMut(T) -> EndType,
+                {
+                    let $context {
+                        mut scenario,
+                        value,
+                        ..
+                    } = self.context;
+                    let value = scenario.retry_step(Keyword::$keyword, s, self.retry, value, fut);
+                    $next {
+                        scenario,
+                        value,
+                        strictness: PhantomData,
+                    }
+                }
+
+                pub async fn $stepf<Closure, Fut, EndType>(
+                    self,
+                    s: impl Display,
+                    fut: Closure,
+                ) -> $next<EndType, S>
+                where
+                    $(S: $transition,)?
+                    Closure: FnMut(T) -> Fut,
+                    Fut: Future<Output = EndType>,
+                {
+                    let $context {
+                        mut scenario,
+                        value,
+                        ..
+                    } = self.context;
+                    let value = scenario
+                        .retry_stepf(Keyword::$keyword, s, self.retry, value, fut)
+                        .await;
+                    $next {
+                        scenario,
+                        value,
+                        strictness: PhantomData,
+                    }
+                }
+            )*
+        }
+
+        impl<T: 'static, S: 'static> $future<T, S> {
+            /// Runs the next step up to `attempts` times until it passes
+            pub fn retry(self, attempts: u32, backoff: Backoff) -> Retrying<Self> {
+                Retrying::new(self, Retry::attempts(attempts, backoff))
+            }
+
+            /// Runs the next step until it passes or `within` expires
+            pub fn eventually(self, within: Duration, backoff: Backoff) -> Retrying<Self> {
+                Retrying::new(self, Retry::eventually(within, backoff))
+            }
+        }
+
+        impl<T: Clone + 'static, S: 'static> Retrying<$future<T, S>> {
+            $(
+                pub fn $step<Closure, EndType>(
+                    self,
+                    s: impl Display + 'static,
+                    fut: Closure,
+                ) -> $next_future<EndType, S>
+                where
+                    $(S: $transition,)?
+                    EndType: 'static,
+                    Closure: FnMut(T) -> EndType + 'static,
+                {
+                    let context =
+                        async move { Retrying::new(self.context.await, self.retry).$step(s, fut) };
+                    $next_future {
+                        context: Box::pin(context),
+                    }
+                }
+
+                pub fn $stepf<Closure, Fut, EndType>(
+                    self,
+                    s: impl Display + 'static,
+                    fut: Closure,
+                ) -> $next_future<EndType, S>
+                where
+                    $(S: $transition,)?
+                    EndType: 'static,
+                    Closure: FnMut(T) -> Fut + 'static,
+                    Fut: Future<Output = EndType> + 'static,
+                {
+                    let context = async move {
+                        Retrying::new(self.context.await, self.retry)
+                            .$stepf(s, fut)
+                            .await
+                    };
+                    $next_future {
+                        context: Box::pin(context),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+impl_retrying_steps!(GivenContext, GivenContextFuture {
+    and, andf: And => GivenContext, GivenContextFuture;
+    when, whenf: When => WhenContext, WhenContextFuture;
+    then, thenf: Then => ThenContext, ThenContextFuture where S: ThenAfterGiven;
+});
+impl_retrying_steps!(WhenContext, WhenContextFuture {
+    when, whenf: When => WhenContext, WhenContextFuture;
+    and, andf: And => WhenContext, WhenContextFuture;
+    then, thenf: Then => ThenContext, ThenContextFuture;
+});
+impl_retrying_steps!(ThenContext, ThenContextFuture {
+    then, thenf: Then => ThenContext, ThenContextFuture;
+    and, andf: And => ThenContext, ThenContextFuture;
+    when, whenf: When => WhenContext, WhenContextFuture where S: WhenAfterThen;
+});
+
+/// Runs a whole scenario again when it panics. Each attempt gets an [`Attempt`] to pass as the writer
+/// of its scenario, so that its steps and the failed attempts are reported by the reporter of `w`.
+pub fn retry_scenario<Scenario>(retry: Retry, w: impl IntoReporter, mut scenario: Scenario)
+where
+    Scenario: FnMut(Attempt),
+{
+    let reporter = Finish(Rc::new(RefCell::new(w.into_reporter())));
+    let started = Instant::now();
+    let mut attempt = 1;
+    loop {
+        match catch_unwind(AssertUnwindSafe(|| scenario(reporter.attempt(attempt)))) {
+            Ok(()) => {
+                reporter.passed(attempt);
+                return;
+            }
+            Err(payload) => {
+                let Some(delay) = retry.next_delay(attempt, started.elapsed()) else {
+                    resume_unwind(payload);
+                };
+                reporter.failed(attempt, payload.as_ref());
+                thread::sleep(delay);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Async version of [`retry_scenario`], sleeping between attempts with [`ThreadTimer`]
+pub async fn retry_scenariof<Scenario, Fut>(
+    retry: Retry,
+    w: impl IntoReporter,
+    mut scenario: Scenario,
+) where
+    Scenario: FnMut(Attempt) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let reporter = Finish(Rc::new(RefCell::new(w.into_reporter())));
+    let started = Instant::now();
+    let mut attempt = 1;
+    loop {
+        match CatchUnwind::new(scenario(reporter.attempt(attempt))).await {
+            Ok(()) => {
+                reporter.passed(attempt);
+                return;
+            }
+            Err(payload) => {
+                let Some(delay) = retry.next_delay(attempt, started.elapsed()) else {
+                    resume_unwind(payload);
+                };
+                reporter.failed(attempt, payload.as_ref());
+                ThreadTimer.sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Attempt of a scenario run by [`retry_scenario`], the writer of the scenario.
+/// Its steps go to the reporter passed to [`retry_scenario`] instead of one of their own.
+pub struct Attempt {
+    number: u32,
+    reporter: Rc<RefCell<Box<dyn Reporter>>>,
+}
+
+impl Attempt {
+    /// Number of the attempt, starting at 1
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+}
+
+impl IntoReporter for Attempt {
+    fn into_reporter(self) -> Box<dyn Reporter> {
+        Box::new(AttemptReporter(self.reporter))
+    }
+}
+
+/// Forwards the steps of an attempt to the reporter of [`retry_scenario`], which finishes once after the last attempt
+struct AttemptReporter(Rc<RefCell<Box<dyn Reporter>>>);
+
+impl Reporter for AttemptReporter {
+    fn scenario(&mut self, title: &str) {
+        self.0.borrow_mut().scenario(title);
+    }
+
+    fn step_started(&mut self, keyword: Keyword, text: &str) {
+        self.0.borrow_mut().step_started(keyword, text);
+    }
+
+    fn step(&mut self, step: &StepReport<'_>) {
+        self.0.borrow_mut().step(step);
+    }
+}
+
+/// Reporter of [`retry_scenario`], finished when dropped so that a last failed attempt still ends the report
+struct Finish(Rc<RefCell<Box<dyn Reporter>>>);
+
+impl Finish {
+    fn attempt(&self, number: u32) -> Attempt {
+        Attempt {
+            number,
+            reporter: self.0.clone(),
+        }
+    }
+
+    fn failed(&self, attempt: u32, payload: &(dyn Any + Send)) {
+        self.0.borrow_mut().retry(attempt, &panic_message(payload));
+    }
+
+    fn passed(&self, attempt: u32) {
+        if attempt > 1 {
+            self.0.borrow_mut().retried(attempt);
+        }
+    }
+}
+
+impl Drop for Finish {
+    fn drop(&mut self) {
+        self.0.borrow_mut().finish();
+    }
+}
@@ -0,0 +1,360 @@
+use core::{fmt::Display, future::Future, pin::Pin, time::Duration};
+use std::{
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    rc::Rc,
+    thread,
+    time::Instant,
+};
+
+use crate::{
+    panic::{expect_panic, panic_message, CatchUnwind, CaughtPanic},
+    timeout::Timeout,
+    IntoReporter, Keyword, Reporter, Retry, Status, StepReport, ThreadTimer, Timer, World,
+};
+
+/// State shared by every context of a single scenario
+pub(crate) struct Scenario {
+    reporter: Box<dyn Reporter>,
+    soft_failures: Vec<String>,
+    started: Instant,
+    step_timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    timer: Rc<dyn Timer>,
+    world: World,
+}
+
+impl Scenario {
+    /// Scenario titled after the running test if it can be detected, see [`test_name`]
+    pub(crate) fn new(reporter: impl IntoReporter) -> Self {
+        Self::named(&test_name().unwrap_or_default(), reporter)
+    }
+
+    pub(crate) fn named(name: &str, reporter: impl IntoReporter) -> Self {
+        let mut reporter = reporter.into_reporter();
+        reporter.scenario(name);
+        Self {
+            reporter,
+            soft_failures: Vec::new(),
+            started: Instant::now(),
+            step_timeout: None,
+            deadline: None,
+            timer: Rc::new(ThreadTimer),
+            world: World::default(),
+        }
+    }
+
+    pub(crate) fn set_step_timeout(&mut self, timeout: Duration) {
+        self.step_timeout = timeout.into();
+    }
+
+    /// The scenario timeout counts from the first step
+    pub(crate) fn set_scenario_timeout(&mut self, timeout: Duration) {
+        self.deadline = (self.started + timeout).into();
+    }
+
+    pub(crate) fn set_timer(&mut self, timer: impl Timer + 'static) {
+        self.timer = Rc::new(timer);
+    }
+
+    pub(crate) fn world(&self) -> World {
+        self.world.clone()
+    }
+
+    /// Timer for the next async step, if it has a time limit
+    fn sleep(&self) -> Option<Pin<Box<dyn Future<Output = ()>>>> {
+        time_limit(self.step_timeout, self.deadline).map(|limit| self.timer.sleep(limit))
+    }
+
+    pub(crate) fn step<EndType>(
+        &mut self,
+        keyword: Keyword,
+        s: impl Display,
+        callback: impl FnOnce() -> EndType,
+    ) -> EndType {
+        let guard = StepGuard::new(self.reporter.as_mut(), keyword, s);
+        let value = callback();
+        guard.passed();
+        value
+    }
+
+    pub(crate) async fn stepf<Fut, EndType>(
+        &mut self,
+        keyword: Keyword,
+        s: impl Display,
+        callback: impl FnOnce() -> Fut,
+    ) -> EndType
+    where
+        Fut: Future<Output = EndType>,
+    {
+        let text = s.to_string();
+        let sleep = self.sleep();
+        let guard = StepGuard::new(self.reporter.as_mut(), keyword, &text);
+        let value = limit(keyword, &text, sleep, callback()).await;
+        guard.passed();
+        value
+    }
+
+    /// Reports a step that wasn't run because an earlier one failed
+    pub(crate) fn skip(&mut self, keyword: Keyword, s: impl Display) {
+        StepGuard::new(self.reporter.as_mut(), keyword, s).report(Status::Skipped);
+    }
+
+    /// Runs the step with a borrow of `value`, recording a panic instead of propagating it
+    pub(crate) fn soft_step<T>(
+        &mut self,
+        keyword: Keyword,
+        s: impl Display,
+        value: &T,
+        callback: impl FnOnce(&T),
+    ) {
+        let text = s.to_string();
+        let guard = StepGuard::new(self.reporter.as_mut(), keyword, &text);
+        match catch_unwind(AssertUnwindSafe(|| callback(value))) {
+            Ok(()) => guard.passed(),
+            Err(payload) => {
+                drop(guard);
+                self.record_soft_failure(keyword, &text, payload.as_ref());
+            }
+        }
+    }
+
+    /// Async [`Self::soft_step`], the future owns what it checks
+    pub(crate) async fn soft_stepf<Fut>(
+        &mut self,
+        keyword: Keyword,
+        s: impl Display,
+        callback: impl FnOnce() -> Fut,
+    ) where
+        Fut: Future<Output = ()>,
+    {
+        let text = s.to_string();
+        let sleep = self.sleep();
+        let guard = StepGuard::new(self.reporter.as_mut(), keyword, &text);
+        let result = match catch_unwind(AssertUnwindSafe(callback)) {
+            Ok(future) => CatchUnwind::new(limit(keyword, &text, sleep, future)).await,
+            Err(payload) => Err(payload),
+        };
+        match result {
+            Ok(()) => guard.passed(),
+            Err(payload) => {
+                drop(guard);
+                self.record_soft_failure(keyword, &text, payload.as_ref());
+            }
+        }
+    }
+
+    /// Runs the step on a new clone of `value` for every attempt until it passes or `retry` gives up
+    pub(crate) fn retry_step<T: Clone, EndType>(
+        &mut self,
+        keyword: Keyword,
+        s: impl Display,
+        retry: Retry,
+        value: T,
+        mut callback: impl FnMut(T) -> EndType,
+    ) -> EndType {
+        let mut guard = StepGuard::new(self.reporter.as_mut(), keyword, s);
+        loop {
+            match catch_unwind(AssertUnwindSafe(|| callback(value.clone()))) {
+                Ok(value) => {
+                    guard.passed();
+                    return value;
+                }
+                Err(payload) => {
+                    let Some(delay) = retry.next_delay(guard.attempts, guard.started.elapsed())
+                    else {
+                        resume_unwind(payload);
+                    };
+                    thread::sleep(delay);
+                    guard.attempts += 1;
+                }
+            }
+        }
+    }
+
+    /// Async [`Self::retry_step`]
+    pub(crate) async fn retry_stepf<T: Clone, Fut, EndType>(
+        &mut self,
+        keyword: Keyword,
+        s: impl Display,
+        retry: Retry,
+        value: T,
+        mut callback: impl FnMut(T) -> Fut,
+    ) -> EndType
+    where
+        Fut: Future<Output = EndType>,
+    {
+        let text = s.to_string();
+        let (step_timeout, deadline, timer) =
+            (self.step_timeout, self.deadline, self.timer.clone());
+        let mut guard = StepGuard::new(self.reporter.as_mut(), keyword, &text);
+        loop {
+            let sleep = time_limit(step_timeout, deadline).map(|limit| timer.sleep(limit));
+            let result = match catch_unwind(AssertUnwindSafe(|| callback(value.clone()))) {
+                Ok(future) => CatchUnwind::new(limit(keyword, &text, sleep, future)).await,
+                Err(payload) => Err(payload),
+            };
+            match result {
+                Ok(value) => {
+                    guard.passed();
+                    return value;
+                }
+                Err(payload) => {
+                    let Some(delay) = retry.next_delay(guard.attempts, guard.started.elapsed())
+                    else {
+                        resume_unwind(payload);
+                    };
+                    timer.sleep(delay).await;
+                    guard.attempts += 1;
+                }
+            }
+        }
+    }
+
+    /// Runs a step that must panic, returning the caught panic
+    pub(crate) fn expect_panic<EndType>(
+        &mut self,
+        keyword: Keyword,
+        s: impl Display,
+        expected: Option<&str>,
+        callback: impl FnOnce() -> EndType,
+    ) -> CaughtPanic {
+        let text = s.to_string();
+        let guard = StepGuard::new(self.reporter.as_mut(), keyword, &text);
+        let result = catch_unwind(AssertUnwindSafe(|| drop(callback())));
+        let caught = expect_panic(keyword, &text, expected, result);
+        guard.passed();
+        caught
+    }
+
+    pub(crate) async fn expect_panicf<Fut: Future>(
+        &mut self,
+        keyword: Keyword,
+        s: impl Display,
+        expected: Option<&str>,
+        callback: impl FnOnce() -> Fut,
+    ) -> CaughtPanic {
+        let text = s.to_string();
+        let sleep = self.sleep();
+        let guard = StepGuard::new(self.reporter.as_mut(), keyword, &text);
+        let result = match catch_unwind(AssertUnwindSafe(callback)) {
+            Ok(future) => CatchUnwind::new(limit(keyword, &text, sleep, future))
+                .await
+                .map(drop),
+            Err(payload) => Err(payload),
+        };
+        let caught = expect_panic(keyword, &text, expected, result);
+        guard.passed();
+        caught
+    }
+
+    fn record_soft_failure(
+        &mut self,
+        keyword: Keyword,
+        text: &str,
+        payload: &(dyn core::any::Any + Send),
+    ) {
+        self.soft_failures
+            .push(format!("{keyword} {text}: {}", panic_message(payload)));
+    }
+}
+
+fn time_limit(step_timeout: Option<Duration>, deadline: Option<Instant>) -> Option<Duration> {
+    let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+    match (step_timeout, remaining) {
+        (Some(step), Some(remaining)) => step.min(remaining).into(),
+        (step, remaining) => step.or(remaining),
+    }
+}
+
+/// Fails the step with its text and elapsed time if `sleep` finishes first
+async fn limit<Fut: Future>(
+    keyword: Keyword,
+    text: &str,
+    sleep: Option<Pin<Box<dyn Future<Output = ()>>>>,
+    future: Fut,
+) -> Fut::Output {
+    let Some(sleep) = sleep else {
+        return future.await;
+    };
+    let started = Instant::now();
+    match Timeout::new(future, sleep).await {
+        Some(value) => value,
+        None => panic!("{keyword} {text} timed out after {:.2?}", started.elapsed()),
+    }
+}
+
+impl Drop for Scenario {
+    fn drop(&mut self) {
+        self.world.clear();
+        self.reporter.finish();
+        if !self.soft_failures.is_empty() && !thread::panicking() {
+            panic!(
+                "{} soft assertions failed:\n{}",
+                self.soft_failures.len(),
+                self.soft_failures.join("\n")
+            );
+        }
+    }
+}
+
+/// Reports the start of a step, then the step as failed if it is dropped before `passed` is called,
+/// e.g. while unwinding from a panicking closure
+struct StepGuard<'a> {
+    reporter: &'a mut dyn Reporter,
+    keyword: Keyword,
+    text: String,
+    started: Instant,
+    attempts: u32,
+    done: bool,
+}
+
+impl<'a> StepGuard<'a> {
+    fn new(reporter: &'a mut dyn Reporter, keyword: Keyword, s: impl Display) -> Self {
+        let text = s.to_string();
+        reporter.step_started(keyword, &text);
+        Self {
+            reporter,
+            keyword,
+            text,
+            started: Instant::now(),
+            attempts: 1,
+            done: false,
+        }
+    }
+
+    fn passed(mut self) {
+        self.report(Status::Passed);
+    }
+
+    fn report(&mut self, status: Status) {
+        self.done = true;
+        self.reporter.step(&StepReport {
+            keyword: self.keyword,
+            text: &self.text,
+            duration: self.started.elapsed(),
+            attempts: self.attempts,
+            status,
+        });
+    }
+}
+
+impl Drop for StepGuard<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.report(Status::Failed);
+        }
+    }
+}
+
+/// Name of the running test, which libtest gives to the thread running it: a path of identifiers
+/// such as `tests::buy_coffee`. Tests run on the main thread (`--test-threads=1`) and steps run on
+/// threads of a runtime (`tokio-runtime-worker`) or spawned by the test have no detectable name.
+fn test_name() -> Option<String> {
+    let thread = thread::current();
+    let name = thread.name()?;
+    let is_identifier = |segment: &str| {
+        segment.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+    };
+    (name != "main" && name.split("::").all(is_identifier)).then(|| name.to_string())
+}
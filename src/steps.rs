@@ -0,0 +1,221 @@
+use core::{fmt::Display, marker::PhantomData};
+use std::rc::Rc;
+
+use crate::{
+    scenario::Scenario, GivenContext, IntoReporter, Keyword, Strict, Strictness, ThenAfterGiven,
+    ThenContext, WhenAfterThen, WhenContext,
+};
+
+type Run<T> = Rc<dyn Fn(&mut Scenario) -> T>;
+
+/// Starts recording the steps of a scenario without running them.
+/// The recorded steps can be cloned, extended differently and run any number of times,
+/// each run starts a new scenario and calls every closure again.
+pub fn given_steps<T, F>(s: impl Display, callback: F) -> GivenSteps<T>
+where
+    F: Fn() -> T + 'static,
+{
+    let text = s.to_string();
+    GivenSteps {
+        run: Rc::new(move |scenario| scenario.step(Keyword::Given, &text, &callback)),
+        strictness: PhantomData,
+    }
+}
+
+fn record<T: 'static, EndType>(
+    run: &Run<T>,
+    keyword: Keyword,
+    s: impl Display,
+    fut: impl Fn(T) -> EndType + 'static,
+) -> Run<EndType> {
+    let run = run.clone();
+    let text = s.to_string();
+    Rc::new(move |scenario| {
+        let value = run(scenario);
+        scenario.step(keyword, &text, || fut(value))
+    })
+}
+
+/// Recorded Given steps, see [`given_steps`]
+pub struct GivenSteps<T, S = Strict> {
+    run: Run<T>,
+    strictness: PhantomData<S>,
+}
+
+impl<T, S> Clone for GivenSteps<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            run: self.run.clone(),
+            strictness: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static, S> GivenSteps<T, S> {
+    /// Changes the steps allowed in the rest of the scenario, see [`Strictness`]
+    pub fn strictness<To: Strictness>(&self) -> GivenSteps<T, To> {
+        GivenSteps {
+            run: self.run.clone(),
+            strictness: PhantomData,
+        }
+    }
+
+    pub fn and<Closure, EndType>(&self, s: impl Display, fut: Closure) -> GivenSteps<EndType, S>
+    where
+        Closure: Fn(T) -> EndType + 'static,
+    {
+        GivenSteps {
+            run: record(&self.run, Keyword::And, s, fut),
+            strictness: PhantomData,
+        }
+    }
+
+    pub fn when<Closure, EndType>(&self, s: impl Display, fut: Closure) -> WhenSteps<EndType, S>
+    where
+        Closure: Fn(T) -> EndType + 'static,
+    {
+        WhenSteps {
+            run: record(&self.run, Keyword::When, s, fut),
+            strictness: PhantomData,
+        }
+    }
+
+    pub fn then<Closure, EndType>(&self, s: impl Display, fut: Closure) -> ThenSteps<EndType, S>
+    where
+        S: ThenAfterGiven,
+        Closure: Fn(T) -> EndType + 'static,
+    {
+        ThenSteps {
+            run: record(&self.run, Keyword::Then, s, fut),
+            strictness: PhantomData,
+        }
+    }
+
+    /// Runs the recorded steps as a new scenario
+    pub fn run(&self, w: impl IntoReporter) -> GivenContext<T, S> {
+        let mut scenario = Scenario::new(w);
+        let value = (self.run)(&mut scenario);
+        GivenContext {
+            scenario,
+            value,
+            strictness: PhantomData,
+        }
+    }
+}
+
+/// Recorded When steps, see [`given_steps`]
+pub struct WhenSteps<T, S = Strict> {
+    run: Run<T>,
+    strictness: PhantomData<S>,
+}
+
+impl<T, S> Clone for WhenSteps<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            run: self.run.clone(),
+            strictness: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static, S> WhenSteps<T, S> {
+    pub fn when<Closure, EndType>(&self, s: impl Display, fut: Closure) -> WhenSteps<EndType, S>
+    where
+        Closure: Fn(T) -> EndType + 'static,
+    {
+        WhenSteps {
+            run: record(&self.run, Keyword::When, s, fut),
+            strictness: PhantomData,
+        }
+    }
+
+    pub fn and<Closure, EndType>(&self, s: impl Display, fut: Closure) -> WhenSteps<EndType, S>
+    where
+        Closure: Fn(T) -> EndType + 'static,
+    {
+        WhenSteps {
+            run: record(&self.run, Keyword::And, s, fut),
+            strictness: PhantomData,
+        }
+    }
+
+    pub fn then<Closure, EndType>(&self, s: impl Display, fut: Closure) -> ThenSteps<EndType, S>
+    where
+        Closure: Fn(T) -> EndType + 'static,
+    {
+        ThenSteps {
+            run: record(&self.run, Keyword::Then, s, fut),
+            strictness: PhantomData,
+        }
+    }
+
+    /// Runs the recorded steps as a new scenario
+    pub fn run(&self, w: impl IntoReporter) -> WhenContext<T, S> {
+        let mut scenario = Scenario::new(w);
+        let value = (self.run)(&mut scenario);
+        WhenContext {
+            scenario,
+            value,
+            strictness: PhantomData,
+        }
+    }
+}
+
+/// Recorded Then steps, see [`given_steps`]
+pub struct ThenSteps<T, S = Strict> {
+    run: Run<T>,
+    strictness: PhantomData<S>,
+}
+
+impl<T, S> Clone for ThenSteps<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            run: self.run.clone(),
+            strictness: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static, S> ThenSteps<T, S> {
+    pub fn then<Closure, EndType>(&self, s: impl Display, fut: Closure) -> ThenSteps<EndType, S>
+    where
+        Closure: Fn(T) -> EndType + 'static,
+    {
+        ThenSteps {
+            run: record(&self.run, Keyword::Then, s, fut),
+            strictness: PhantomData,
+        }
+    }
+
+    pub fn and<Closure, EndType>(&self, s: impl Display, fut: Closure) -> ThenSteps<EndType, S>
+    where
+        Closure: Fn(T) -> EndType + 'static,
+    {
+        ThenSteps {
+            run: record(&self.run, Keyword::And, s, fut),
+            strictness: PhantomData,
+        }
+    }
+
+    pub fn when<Closure, EndType>(&self, s: impl Display, fut: Closure) -> WhenSteps<EndType, S>
+    where
+        S: WhenAfterThen,
+        Closure: Fn(T) -> EndType + 'static,
+    {
+        WhenSteps {
+            run: record(&self.run, Keyword::When, s, fut),
+            strictness: PhantomData,
+        }
+    }
+
+    /// Runs the recorded steps as a new scenario
+    pub fn run(&self, w: impl IntoReporter) -> ThenContext<T, S> {
+        let mut scenario = Scenario::new(w);
+        let value = (self.run)(&mut scenario);
+        ThenContext {
+            scenario,
+            value,
+            strictness: PhantomData,
+        }
+    }
+}
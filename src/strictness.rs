@@ -0,0 +1,211 @@
+use core::{fmt::Display, future::Future, marker::PhantomData};
+
+use crate::{
+    GivenContext, GivenContextFuture, Keyword, ThenContext, ThenContextFuture, WhenContext,
+    WhenContextFuture,
+};
+
+/// Order in which the steps of a scenario may come, the last type parameter of the contexts.
+/// A step that doesn't fit the order is a compile error.
+///
+/// | after a      | [`Strict`]   | [`MultiPhase`]     | [`Relaxed`]              |
+/// |--------------|--------------|--------------------|--------------------------|
+/// | Given step   | Given, When  | Given, When        | Given, When, Then        |
+/// | When step    | When, Then   | When, Then         | When, Then               |
+/// | Then step    | Then         | Then, When         | Then, When               |
+///
+/// `and` always repeats the keyword of the previous step. Scenarios are [`Strict`] unless
+/// [`GivenContext::strictness`] says otherwise.
+pub trait Strictness {}
+
+/// Given steps, then When steps, then Then steps
+pub struct Strict;
+
+/// Like [`Strict`], but a When step may follow a Then step to start another phase,
+/// as in `When ... Then ... When ... Then`
+pub struct MultiPhase;
+
+/// Like [`MultiPhase`], but a Then step may also directly follow a Given step
+pub struct Relaxed;
+
+impl Strictness for Strict {}
+impl Strictness for MultiPhase {}
+impl Strictness for Relaxed {}
+
+/// Strictness allowing a Then step right after a Given step
+#[diagnostic::on_unimplemented(
+    message = "a Then step can't follow a Given step of a `{Self}` scenario",
+    label = "Given steps are followed by `and` or `when`",
+    note = "add a When step, or call `.strictness::<Relaxed>()` after the first Given step"
+)]
+pub trait ThenAfterGiven: Strictness {}
+
+/// Strictness allowing a When step after a Then step
+#[diagnostic::on_unimplemented(
+    message = "a When step can't follow a Then step of a `{Self}` scenario",
+    label = "Then steps are followed by `and` or `then`",
+    note = "call `.strictness::<MultiPhase>()` after the first Given step to run several When/Then phases"
+)]
+pub trait WhenAfterThen: Strictness {}
+
+impl ThenAfterGiven for Relaxed {}
+impl WhenAfterThen for MultiPhase {}
+impl WhenAfterThen for Relaxed {}
+
+impl<T, S> GivenContext<T, S> {
+    /// Changes the steps allowed in the rest of the scenario, see [`Strictness`]
+    pub fn strictness<To: Strictness>(self) -> GivenContext<T, To> {
+        GivenContext {
+            scenario: self.scenario,
+            value: self.value,
+            strictness: PhantomData,
+        }
+    }
+
+    pub fn then<Closure, EndType>(self, s: impl Display, fut: Closure) -> ThenContext<EndType, S>
+    where
+        S: ThenAfterGiven,
+        Closure: FnOnce(T) -> EndType,
+    {
+        self.into_then().then(s, fut)
+    }
+
+    pub async fn thenf<Closure, Fut, EndType>(
+        self,
+        s: impl Display,
+        fut: Closure,
+    ) -> ThenContext<EndType, S>
+    where
+        S: ThenAfterGiven,
+        Closure: FnOnce(T) -> Fut,
+        Fut: Future<Output = EndType>,
+    {
+        self.into_then().thenf(s, fut).await
+    }
+
+    fn into_then(self) -> ThenContext<T, S> {
+        ThenContext {
+            scenario: self.scenario,
+            value: self.value,
+            strictness: PhantomData,
+        }
+    }
+}
+
+impl<T, S> ThenContext<T, S> {
+    pub fn when<Closure, EndType>(
+        mut self,
+        s: impl Display,
+        fut: Closure,
+    ) -> WhenContext<EndType, S>
+    where
+        S: WhenAfterThen,
+        Closure: FnOnce(T) -> EndType,
+    {
+        let value = self.scenario.step(Keyword::When, s, || fut(self.value));
+        WhenContext {
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
+        }
+    }
+
+    pub async fn whenf<Closure, Fut, EndType>(
+        mut self,
+        s: impl Display,
+        fut: Closure,
+    ) -> WhenContext<EndType, S>
+    where
+        S: WhenAfterThen,
+        Closure: FnOnce(T) -> Fut,
+        Fut: Future<Output = EndType>,
+    {
+        let value = self
+            .scenario
+            .stepf(Keyword::When, s, || fut(self.value))
+            .await;
+        WhenContext {
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static, S: 'static> GivenContextFuture<T, S> {
+    /// Changes the steps allowed in the rest of the scenario, see [`Strictness`]
+    pub fn strictness<To: Strictness + 'static>(self) -> GivenContextFuture<T, To> {
+        let context = async { self.await.strictness() };
+        GivenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+
+    pub fn then<Closure, EndType>(
+        self,
+        s: impl Display + 'static,
+        fut: Closure,
+    ) -> ThenContextFuture<EndType, S>
+    where
+        S: ThenAfterGiven,
+        EndType: 'static,
+        Closure: FnOnce(T) -> EndType + 'static,
+    {
+        let context = async { self.await.then(s, fut) };
+        ThenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+
+    pub fn thenf<Closure, Fut, EndType>(
+        self,
+        s: impl Display + 'static,
+        fut: Closure,
+    ) -> ThenContextFuture<EndType, S>
+    where
+        S: ThenAfterGiven,
+        EndType: 'static,
+        Closure: FnOnce(T) -> Fut + 'static,
+        Fut: Future<Output = EndType> + 'static,
+    {
+        let context = async { self.await.thenf(s, fut).await };
+        ThenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+}
+
+impl<T: 'static, S: 'static> ThenContextFuture<T, S> {
+    pub fn when<Closure, EndType>(
+        self,
+        s: impl Display + 'static,
+        fut: Closure,
+    ) -> WhenContextFuture<EndType, S>
+    where
+        S: WhenAfterThen,
+        EndType: 'static,
+        Closure: FnOnce(T) -> EndType + 'static,
+    {
+        let context = async { self.await.when(s, fut) };
+        WhenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+
+    pub fn whenf<Closure, Fut, EndType>(
+        self,
+        s: impl Display + 'static,
+        fut: Closure,
+    ) -> WhenContextFuture<EndType, S>
+    where
+        S: WhenAfterThen,
+        EndType: 'static,
+        Closure: FnOnce(T) -> Fut + 'static,
+        Fut: Future<Output = EndType> + 'static,
+    {
+        let context = async { self.await.whenf(s, fut).await };
+        WhenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+}
@@ -1,20 +1,32 @@
-use core::{fmt::Display, future::Future, pin::Pin};
-use std::io::Write;
+use core::{fmt::Display, future::Future, marker::PhantomData, pin::Pin};
 
-pub struct ThenContext<T> {
-    pub(crate) writer: Box<dyn Write>,
+use crate::{scenario::Scenario, Keyword, Strict};
+
+pub struct ThenContext<T, S = Strict> {
+    pub(crate) scenario: Scenario,
     pub(crate) value: T,
+    pub(crate) strictness: PhantomData<S>,
 }
 
-impl<T> ThenContext<T> {
-    pub fn then<Closure, EndType>(mut self, s: impl Display, fut: Closure) -> ThenContext<EndType>
+impl<T, S> ThenContext<T, S> {
+    /// Ends the scenario and returns the value of its last step
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    pub fn then<Closure, EndType>(
+        mut self,
+        s: impl Display,
+        fut: Closure,
+    ) -> ThenContext<EndType, S>
     where
         Closure: FnOnce(T) -> EndType,
     {
-        writeln!(self.writer, "Then {}", s).expect("writing failed");
+        let value = self.scenario.step(Keyword::Then, s, || fut(self.value));
         ThenContext {
-            writer: self.writer,
-            value: fut(self.value),
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
         }
     }
 
@@ -22,26 +34,31 @@ impl<T> ThenContext<T> {
         mut self,
         s: impl Display,
         fut: Closure,
-    ) -> ThenContext<EndType>
+    ) -> ThenContext<EndType, S>
     where
         Closure: FnOnce(T) -> Fut,
         Fut: Future<Output = EndType>,
     {
-        writeln!(self.writer, "Then {}", s).expect("writing failed");
+        let value = self
+            .scenario
+            .stepf(Keyword::Then, s, || fut(self.value))
+            .await;
         ThenContext {
-            writer: self.writer,
-            value: fut(self.value).await,
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
         }
     }
 
-    pub fn and<Closure, EndType>(mut self, s: impl Display, fut: Closure) -> ThenContext<EndType>
+    pub fn and<Closure, EndType>(mut self, s: impl Display, fut: Closure) -> ThenContext<EndType, S>
     where
         Closure: FnOnce(T) -> EndType,
     {
-        writeln!(self.writer, "And {}", s).expect("writing failed");
+        let value = self.scenario.step(Keyword::And, s, || fut(self.value));
         ThenContext {
-            writer: self.writer,
-            value: fut(self.value),
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
         }
     }
 
@@ -49,26 +66,187 @@ impl<T> ThenContext<T> {
         mut self,
         s: impl Display,
         fut: Closure,
-    ) -> ThenContext<EndType>
+    ) -> ThenContext<EndType, S>
     where
         Closure: FnOnce(T) -> Fut,
         Fut: Future<Output = EndType>,
     {
-        writeln!(self.writer, "And {}", s).expect("writing failed");
+        let value = self
+            .scenario
+            .stepf(Keyword::And, s, || fut(self.value))
+            .await;
         ThenContext {
-            writer: self.writer,
-            value: fut(self.value).await,
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
+        }
+    }
+}
+
+impl<T, S> ThenContext<T, S> {
+    /// Switches the remaining Then steps to soft assertions, see [`SoftThenContext`]
+    pub fn soft(self) -> SoftThenContext<T, S> {
+        SoftThenContext {
+            scenario: self.scenario,
+            value: self.value,
+            strictness: PhantomData,
+        }
+    }
+}
+
+/// Then steps that record their failures instead of stopping the scenario.
+/// Each step checks the value of the last When step, the remaining steps still run after a failure
+/// and the scenario fails when it ends, listing the failures of every step.
+pub struct SoftThenContext<T, S = Strict> {
+    pub(crate) scenario: Scenario,
+    pub(crate) value: T,
+    pub(crate) strictness: PhantomData<S>,
+}
+
+impl<T, S> SoftThenContext<T, S> {
+    /// Ends the scenario and returns the checked value
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    pub fn then<Closure>(mut self, s: impl Display, fut: Closure) -> SoftThenContext<T, S>
+    where
+        Closure: FnOnce(&T),
+    {
+        self.scenario.soft_step(Keyword::Then, s, &self.value, fut);
+        self
+    }
+
+    /// Async step, called with a copy of the value
+    pub async fn thenf<Closure, Fut>(
+        mut self,
+        s: impl Display,
+        fut: Closure,
+    ) -> SoftThenContext<T, S>
+    where
+        T: Clone,
+        Closure: FnOnce(T) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let value = self.value.clone();
+        self.scenario
+            .soft_stepf(Keyword::Then, s, || fut(value))
+            .await;
+        self
+    }
+
+    pub fn and<Closure>(mut self, s: impl Display, fut: Closure) -> SoftThenContext<T, S>
+    where
+        Closure: FnOnce(&T),
+    {
+        self.scenario.soft_step(Keyword::And, s, &self.value, fut);
+        self
+    }
+
+    /// Async step, called with a copy of the value
+    pub async fn andf<Closure, Fut>(
+        mut self,
+        s: impl Display,
+        fut: Closure,
+    ) -> SoftThenContext<T, S>
+    where
+        T: Clone,
+        Closure: FnOnce(T) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let value = self.value.clone();
+        self.scenario
+            .soft_stepf(Keyword::And, s, || fut(value))
+            .await;
+        self
+    }
+}
+
+#[must_use = "Context must be awaited"]
+pub struct SoftThenContextFuture<T, S = Strict> {
+    pub(crate) context: Pin<Box<dyn Future<Output = SoftThenContext<T, S>>>>,
+}
+
+impl<T, S> Future for SoftThenContextFuture<T, S> {
+    type Output = SoftThenContext<T, S>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        self.context.as_mut().poll(cx)
+    }
+}
+
+impl<T: 'static, S: 'static> SoftThenContextFuture<T, S> {
+    pub fn then<Closure>(
+        self,
+        s: impl Display + 'static,
+        fut: Closure,
+    ) -> SoftThenContextFuture<T, S>
+    where
+        Closure: FnOnce(&T) + 'static,
+    {
+        let context = async { self.context.await.then(s, fut) };
+        SoftThenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+
+    pub fn thenf<Closure, Fut>(
+        self,
+        s: impl Display + 'static,
+        fut: Closure,
+    ) -> SoftThenContextFuture<T, S>
+    where
+        T: Clone,
+        Closure: FnOnce(T) -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let context = async { self.context.await.thenf(s, fut).await };
+        SoftThenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+
+    pub fn and<Closure>(
+        self,
+        s: impl Display + 'static,
+        fut: Closure,
+    ) -> SoftThenContextFuture<T, S>
+    where
+        Closure: FnOnce(&T) + 'static,
+    {
+        let context = async { self.context.await.and(s, fut) };
+        SoftThenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+
+    pub fn andf<Closure, Fut>(
+        self,
+        s: impl Display + 'static,
+        fut: Closure,
+    ) -> SoftThenContextFuture<T, S>
+    where
+        T: Clone,
+        Closure: FnOnce(T) -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let context = async { self.context.await.andf(s, fut).await };
+        SoftThenContextFuture {
+            context: Box::pin(context),
         }
     }
 }
 
 #[must_use = "Context must be awaited"]
-pub struct ThenContextFuture<T> {
-    pub(crate) context: Pin<Box<dyn Future<Output = ThenContext<T>>>>,
+pub struct ThenContextFuture<T, S = Strict> {
+    pub(crate) context: Pin<Box<dyn Future<Output = ThenContext<T, S>>>>,
 }
 
-impl<T> Future for ThenContextFuture<T> {
-    type Output = ThenContext<T>;
+impl<T, S> Future for ThenContextFuture<T, S> {
+    type Output = ThenContext<T, S>;
 
     fn poll(
         mut self: Pin<&mut Self>,
@@ -78,12 +256,23 @@ impl<T> Future for ThenContextFuture<T> {
     }
 }
 
-impl<T> ThenContextFuture<T> {
+impl<T, S: 'static> ThenContextFuture<T, S> {
+    /// Switches the remaining Then steps to soft assertions, see [`SoftThenContext`]
+    pub fn soft(self) -> SoftThenContextFuture<T, S>
+    where
+        T: 'static,
+    {
+        let context = async { self.context.await.soft() };
+        SoftThenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+
     pub fn andf<Closure, Fut, EndType>(
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> ThenContextFuture<EndType>
+    ) -> ThenContextFuture<EndType, S>
     where
         T: 'static,
         EndType: 'static,
@@ -93,8 +282,9 @@ impl<T> ThenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             ThenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .andf(s, fut)
             .await
@@ -109,7 +299,7 @@ impl<T> ThenContextFuture<T> {
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> ThenContextFuture<EndType>
+    ) -> ThenContextFuture<EndType, S>
     where
         T: 'static,
         EndType: 'static,
@@ -118,8 +308,9 @@ impl<T> ThenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             ThenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .and(s, fut)
         };
@@ -133,7 +324,7 @@ impl<T> ThenContextFuture<T> {
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> ThenContextFuture<EndType>
+    ) -> ThenContextFuture<EndType, S>
     where
         T: 'static,
         EndType: 'static,
@@ -143,8 +334,9 @@ impl<T> ThenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             ThenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .thenf(s, fut)
             .await
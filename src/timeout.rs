@@ -0,0 +1,218 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Condvar, Mutex, OnceLock,
+    },
+    thread,
+    time::Instant,
+};
+
+/// Source of delays used to enforce step and scenario timeouts
+pub trait Timer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()>>>;
+}
+
+/// Sleeps on a timer thread shared by every scenario, works with any executor
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreadTimer;
+
+impl Timer for ThreadTimer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        Box::pin(ThreadSleep {
+            duration,
+            key: None,
+        })
+    }
+}
+
+/// Wakers of the pending sleeps by deadline, woken by a single thread started with the first sleep
+struct TimerQueue {
+    sleeps: Mutex<BTreeMap<(Instant, u64), Waker>>,
+    changed: Condvar,
+    next_id: AtomicU64,
+}
+
+impl TimerQueue {
+    fn get() -> &'static TimerQueue {
+        static QUEUE: OnceLock<TimerQueue> = OnceLock::new();
+        QUEUE.get_or_init(|| {
+            thread::Builder::new()
+                .name("gnerkinf-timer".into())
+                .spawn(|| TimerQueue::get().run())
+                .expect("timer thread");
+            TimerQueue {
+                sleeps: Mutex::default(),
+                changed: Condvar::new(),
+                next_id: AtomicU64::new(0),
+            }
+        })
+    }
+
+    fn run(&self) {
+        let mut sleeps = self.sleeps.lock().expect("timer queue lock");
+        loop {
+            let now = Instant::now();
+            let mut elapsed = Vec::new();
+            while let Some(entry) = sleeps.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+                elapsed.push(entry.remove());
+            }
+            if !elapsed.is_empty() {
+                // wake outside the lock, an executor may poll the sleep right away
+                drop(sleeps);
+                elapsed.into_iter().for_each(Waker::wake);
+                sleeps = self.sleeps.lock().expect("timer queue lock");
+                continue;
+            }
+            sleeps = match sleeps.keys().next() {
+                Some(&(deadline, _)) => {
+                    let timeout = deadline.saturating_duration_since(now);
+                    self.changed
+                        .wait_timeout(sleeps, timeout)
+                        .expect("timer queue lock")
+                        .0
+                }
+                None => self.changed.wait(sleeps).expect("timer queue lock"),
+            };
+        }
+    }
+}
+
+struct ThreadSleep {
+    duration: Duration,
+    /// Deadline and id in the [`TimerQueue`], set by the first poll
+    key: Option<(Instant, u64)>,
+}
+
+impl Future for ThreadSleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let queue = TimerQueue::get();
+        let duration = self.duration;
+        let key = *self.key.get_or_insert_with(|| {
+            let id = queue.next_id.fetch_add(1, Ordering::Relaxed);
+            (Instant::now() + duration, id)
+        });
+        if Instant::now() >= key.0 {
+            return Poll::Ready(());
+        }
+        let mut sleeps = queue.sleeps.lock().expect("timer queue lock");
+        sleeps.insert(key, cx.waker().clone());
+        queue.changed.notify_one();
+        Poll::Pending
+    }
+}
+
+impl Drop for ThreadSleep {
+    fn drop(&mut self) {
+        // a step that finished in time cancels its sleep
+        if let Some(key) = self.key {
+            let mut sleeps = TimerQueue::get().sleeps.lock().expect("timer queue lock");
+            sleeps.remove(&key);
+        }
+    }
+}
+
+/// Uses `tokio::time::sleep`, requires a tokio runtime with the time driver enabled
+#[cfg(feature = "tokio")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioTimer;
+
+#[cfg(feature = "tokio")]
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Resolves to `None` if `sleep` finishes before `future`
+pub(crate) struct Timeout<Fut> {
+    future: Pin<Box<Fut>>,
+    sleep: Pin<Box<dyn Future<Output = ()>>>,
+}
+
+impl<Fut> Timeout<Fut> {
+    pub(crate) fn new(future: Fut, sleep: Pin<Box<dyn Future<Output = ()>>>) -> Self {
+        Self {
+            future: Box::pin(future),
+            sleep,
+        }
+    }
+}
+
+impl<Fut: Future> Future for Timeout<Fut> {
+    type Output = Option<Fut::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(value) = self.future.as_mut().poll(cx) {
+            return Poll::Ready(value.into());
+        }
+        self.sleep.as_mut().poll(cx).map(|_| None)
+    }
+}
+
+/// `step_timeout`, `scenario_timeout` and `timer` of a context and of its future
+macro_rules! impl_timeouts {
+    ($context:ident, $future:ident) => {
+        impl<T, S> crate::$context<T, S> {
+            /// Fails every following async step that runs longer than `timeout`
+            pub fn step_timeout(mut self, timeout: Duration) -> Self {
+                self.scenario.set_step_timeout(timeout);
+                self
+            }
+
+            /// Fails the async step still running `timeout` after the scenario started
+            pub fn scenario_timeout(mut self, timeout: Duration) -> Self {
+                self.scenario.set_scenario_timeout(timeout);
+                self
+            }
+
+            /// Timer enforcing the timeouts, [`crate::ThreadTimer`] by default
+            pub fn timer(mut self, timer: impl Timer + 'static) -> Self {
+                self.scenario.set_timer(timer);
+                self
+            }
+        }
+
+        impl<T: 'static, S: 'static> crate::$future<T, S> {
+            /// Fails every following async step that runs longer than `timeout`
+            pub fn step_timeout(self, timeout: Duration) -> Self {
+                self.configure(move |context| context.step_timeout(timeout))
+            }
+
+            /// Fails the async step still running `timeout` after the scenario started
+            pub fn scenario_timeout(self, timeout: Duration) -> Self {
+                self.configure(move |context| context.scenario_timeout(timeout))
+            }
+
+            /// Timer enforcing the timeouts, [`crate::ThreadTimer`] by default
+            pub fn timer(self, timer: impl Timer + 'static) -> Self {
+                self.configure(move |context| context.timer(timer))
+            }
+
+            fn configure(
+                self,
+                configure: impl FnOnce(crate::$context<T, S>) -> crate::$context<T, S> + 'static,
+            ) -> Self {
+                let context = async move { configure(self.context.await) };
+                Self {
+                    context: Box::pin(context),
+                }
+            }
+        }
+    };
+}
+
+impl_timeouts!(GivenContext, GivenContextFuture);
+impl_timeouts!(WhenContext, WhenContextFuture);
+impl_timeouts!(ThenContext, ThenContextFuture);
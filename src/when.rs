@@ -1,21 +1,30 @@
-use core::{fmt::Display, future::Future, pin::Pin};
-use std::io::Write;
+use core::{fmt::Display, future::Future, marker::PhantomData, pin::Pin};
 
-use crate::{ThenContext, ThenContextFuture};
-pub struct WhenContext<T> {
-    pub(crate) writer: Box<dyn Write>,
+use crate::{
+    scenario::Scenario, Keyword, SoftThenContext, SoftThenContextFuture, Strict, ThenContext,
+    ThenContextFuture,
+};
+
+pub struct WhenContext<T, S = Strict> {
+    pub(crate) scenario: Scenario,
     pub(crate) value: T,
+    pub(crate) strictness: PhantomData<S>,
 }
 
-impl<T> WhenContext<T> {
-    pub fn when<Closure, EndType>(mut self, s: impl Display, fut: Closure) -> WhenContext<EndType>
+impl<T, S> WhenContext<T, S> {
+    pub fn when<Closure, EndType>(
+        mut self,
+        s: impl Display,
+        fut: Closure,
+    ) -> WhenContext<EndType, S>
     where
         Closure: FnOnce(T) -> EndType,
     {
-        writeln!(self.writer, "When {}", s).expect("writing failed");
+        let value = self.scenario.step(Keyword::When, s, || fut(self.value));
         WhenContext {
-            writer: self.writer,
-            value: fut(self.value),
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
         }
     }
 
@@ -23,26 +32,31 @@ impl<T> WhenContext<T> {
         mut self,
         s: impl Display,
         fut: Closure,
-    ) -> WhenContext<EndType>
+    ) -> WhenContext<EndType, S>
     where
         Closure: FnOnce(T) -> Fut,
         Fut: Future<Output = EndType>,
     {
-        writeln!(self.writer, "When {}", s).expect("writing failed");
+        let value = self
+            .scenario
+            .stepf(Keyword::When, s, || fut(self.value))
+            .await;
         WhenContext {
-            writer: self.writer,
-            value: fut(self.value).await,
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
         }
     }
 
-    pub fn and<Closure, EndType>(mut self, s: impl Display, fut: Closure) -> WhenContext<EndType>
+    pub fn and<Closure, EndType>(mut self, s: impl Display, fut: Closure) -> WhenContext<EndType, S>
     where
         Closure: FnOnce(T) -> EndType,
     {
-        writeln!(self.writer, "And {}", s).expect("writing failed");
+        let value = self.scenario.step(Keyword::And, s, || fut(self.value));
         WhenContext {
-            writer: self.writer,
-            value: fut(self.value),
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
         }
     }
 
@@ -50,15 +64,19 @@ impl<T> WhenContext<T> {
         mut self,
         s: impl Display,
         fut: Closure,
-    ) -> WhenContext<EndType>
+    ) -> WhenContext<EndType, S>
     where
         Closure: FnOnce(T) -> Fut,
         Fut: Future<Output = EndType>,
     {
-        writeln!(self.writer, "And {}", s).expect("writing failed");
+        let value = self
+            .scenario
+            .stepf(Keyword::And, s, || fut(self.value))
+            .await;
         WhenContext {
-            writer: self.writer,
-            value: fut(self.value).await,
+            scenario: self.scenario,
+            value,
+            strictness: PhantomData,
         }
     }
 
@@ -66,14 +84,15 @@ impl<T> WhenContext<T> {
         self,
         s: impl Display,
         fut: Closure,
-    ) -> ThenContext<EndType>
+    ) -> ThenContext<EndType, S>
     where
         Closure: FnOnce(T) -> Fut,
         Fut: Future<Output = EndType>,
     {
         ThenContext {
-            writer: self.writer,
+            scenario: self.scenario,
             value: self.value,
+            strictness: PhantomData,
         }
         .thenf(s, fut)
         .await
@@ -83,16 +102,18 @@ impl<T> WhenContext<T> {
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> ThenContextFuture<EndType>
+    ) -> ThenContextFuture<EndType, S>
     where
         T: 'static,
+        S: 'static,
         EndType: 'static,
         Closure: FnOnce(T) -> Fut + 'static,
         Fut: Future<Output = EndType> + 'static,
     {
         let context = ThenContext {
-            writer: self.writer,
+            scenario: self.scenario,
             value: self.value,
+            strictness: PhantomData,
         }
         .thenf(s, fut);
 
@@ -101,25 +122,39 @@ impl<T> WhenContext<T> {
         }
     }
 
-    pub fn then<Closure, EndType>(self, s: impl Display, fut: Closure) -> ThenContext<EndType>
+    pub fn then<Closure, EndType>(self, s: impl Display, fut: Closure) -> ThenContext<EndType, S>
     where
         Closure: FnOnce(T) -> EndType,
     {
         ThenContext {
-            writer: self.writer,
+            scenario: self.scenario,
+            value: self.value,
+            strictness: PhantomData,
+        }
+        .then(s, fut)
+    }
+
+    /// First step of soft Then assertions, see [`SoftThenContext`]
+    pub fn then_soft<Closure>(self, s: impl Display, fut: Closure) -> SoftThenContext<T, S>
+    where
+        Closure: FnOnce(&T),
+    {
+        SoftThenContext {
+            scenario: self.scenario,
             value: self.value,
+            strictness: PhantomData,
         }
         .then(s, fut)
     }
 }
 
 #[must_use = "Context must be awaited"]
-pub struct WhenContextFuture<T> {
-    pub(crate) context: Pin<Box<dyn Future<Output = WhenContext<T>>>>,
+pub struct WhenContextFuture<T, S = Strict> {
+    pub(crate) context: Pin<Box<dyn Future<Output = WhenContext<T, S>>>>,
 }
 
-impl<T> Future for WhenContextFuture<T> {
-    type Output = WhenContext<T>;
+impl<T, S> Future for WhenContextFuture<T, S> {
+    type Output = WhenContext<T, S>;
 
     fn poll(
         mut self: Pin<&mut Self>,
@@ -129,12 +164,28 @@ impl<T> Future for WhenContextFuture<T> {
     }
 }
 
-impl<T> WhenContextFuture<T> {
+impl<T, S: 'static> WhenContextFuture<T, S> {
+    /// First step of soft Then assertions, see [`SoftThenContext`]
+    pub fn then_soft<Closure>(
+        self,
+        s: impl Display + 'static,
+        fut: Closure,
+    ) -> SoftThenContextFuture<T, S>
+    where
+        T: 'static,
+        Closure: FnOnce(&T) + 'static,
+    {
+        let context = async { self.context.await.then_soft(s, fut) };
+        SoftThenContextFuture {
+            context: Box::pin(context),
+        }
+    }
+
     pub fn andf<Closure, Fut, EndType>(
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> WhenContextFuture<EndType>
+    ) -> WhenContextFuture<EndType, S>
     where
         T: 'static,
         EndType: 'static,
@@ -144,8 +195,9 @@ impl<T> WhenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             WhenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .andf(s, fut)
             .await
@@ -160,7 +212,7 @@ impl<T> WhenContextFuture<T> {
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> WhenContextFuture<EndType>
+    ) -> WhenContextFuture<EndType, S>
     where
         T: 'static,
         EndType: 'static,
@@ -169,8 +221,9 @@ impl<T> WhenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             WhenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .and(s, fut)
         };
@@ -184,7 +237,7 @@ impl<T> WhenContextFuture<T> {
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> ThenContextFuture<EndType>
+    ) -> ThenContextFuture<EndType, S>
     where
         T: 'static,
         EndType: 'static,
@@ -194,8 +247,9 @@ impl<T> WhenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             ThenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .thenf(s, fut)
             .await
@@ -210,7 +264,7 @@ impl<T> WhenContextFuture<T> {
         self,
         s: impl Display + 'static,
         fut: Closure,
-    ) -> ThenContextFuture<EndType>
+    ) -> ThenContextFuture<EndType, S>
     where
         T: 'static,
         EndType: 'static,
@@ -219,8 +273,9 @@ impl<T> WhenContextFuture<T> {
         let context = async {
             let p = self.context.await;
             ThenContext {
-                writer: p.writer,
+                scenario: p.scenario,
                 value: p.value,
+                strictness: PhantomData,
             }
             .then(s, fut)
         };
@@ -0,0 +1,340 @@
+use core::{
+    any::{type_name, Any, TypeId},
+    cell::RefCell,
+    fmt::Display,
+    future::Future,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    scenario::Scenario, GivenContext, GivenContextFuture, IntoReporter, Keyword, ThenAfterGiven,
+    ThenContext, ThenContextFuture, WhenAfterThen, WhenContext, WhenContextFuture,
+};
+
+/// Values of the running scenario keyed by their type, emptied when the scenario ends.
+/// Cloning gives another handle to the same values.
+/// Each value is borrowed on its own, like a `RefCell` per type: a value taken with `get_mut`
+/// can't be read until it is released, but values of other types can.
+#[derive(Clone, Default)]
+pub struct World {
+    values: Rc<RefCell<HashMap<TypeId, Entry>>>,
+}
+
+enum Entry {
+    Stored(Rc<dyn Any>),
+    /// Taken out by a [`WorldRefMut`], which puts it back when dropped
+    Borrowed,
+}
+
+impl Entry {
+    /// Whether a [`WorldRef`] or [`WorldRefMut`] still uses the value
+    fn is_borrowed(&self) -> bool {
+        match self {
+            Entry::Stored(value) => Rc::strong_count(value) > 1,
+            Entry::Borrowed => true,
+        }
+    }
+
+    fn into_value<V: Any>(self) -> V {
+        match self {
+            Entry::Stored(value) => {
+                let value = value.downcast().expect("world value of its own type");
+                Rc::try_unwrap(value).unwrap_or_else(|_| borrowed::<V>())
+            }
+            Entry::Borrowed => borrowed::<V>(),
+        }
+    }
+}
+
+fn borrowed<V>() -> ! {
+    panic!(
+        "{} of the scenario world is already borrowed",
+        type_name::<V>()
+    )
+}
+
+impl World {
+    /// Stores `value`, returning the value of the same type it replaces.
+    /// Panics if the value it replaces is borrowed.
+    pub fn insert<V: Any>(&self, value: V) -> Option<V> {
+        let mut values = self.values.borrow_mut();
+        // checked first so that the world is left unchanged
+        if values
+            .get(&TypeId::of::<V>())
+            .is_some_and(Entry::is_borrowed)
+        {
+            drop(values);
+            borrowed::<V>();
+        }
+        let old = values.insert(TypeId::of::<V>(), Entry::Stored(Rc::new(value)));
+        old.map(Entry::into_value)
+    }
+
+    /// Panics if no `V` was inserted, or if it is borrowed by `get_mut`
+    pub fn get<V: Any>(&self) -> WorldRef<V> {
+        self.try_get().unwrap_or_else(|| self.unavailable::<V>())
+    }
+
+    /// Panics if no `V` was inserted, or if it is already borrowed
+    pub fn get_mut<V: Any>(&self) -> WorldRefMut<V> {
+        self.try_get_mut()
+            .unwrap_or_else(|| self.unavailable::<V>())
+    }
+
+    /// `None` if no `V` was inserted or if it is borrowed by `get_mut`
+    pub fn try_get<V: Any>(&self) -> Option<WorldRef<V>> {
+        match self.values.borrow().get(&TypeId::of::<V>())? {
+            Entry::Stored(value) => Some(WorldRef(
+                value
+                    .clone()
+                    .downcast()
+                    .expect("world value of its own type"),
+            )),
+            Entry::Borrowed => None,
+        }
+    }
+
+    /// `None` if no `V` was inserted or if it is already borrowed
+    pub fn try_get_mut<V: Any>(&self) -> Option<WorldRefMut<V>> {
+        let mut values = self.values.borrow_mut();
+        let entry = values.get_mut(&TypeId::of::<V>())?;
+        if entry.is_borrowed() {
+            return None;
+        }
+        let value = core::mem::replace(entry, Entry::Borrowed).into_value();
+        Some(WorldRefMut {
+            values: self.values.clone(),
+            value: Some(value),
+        })
+    }
+
+    /// Panics if the value is borrowed
+    pub fn remove<V: Any>(&self) -> Option<V> {
+        let mut values = self.values.borrow_mut();
+        if values
+            .get(&TypeId::of::<V>())
+            .is_some_and(Entry::is_borrowed)
+        {
+            drop(values);
+            borrowed::<V>();
+        }
+        let entry = values.remove(&TypeId::of::<V>());
+        entry.map(Entry::into_value)
+    }
+
+    pub fn contains<V: Any>(&self) -> bool {
+        self.values.borrow().contains_key(&TypeId::of::<V>())
+    }
+
+    fn unavailable<V: Any>(&self) -> ! {
+        if self.contains::<V>() {
+            borrowed::<V>();
+        }
+        panic!("no {} in the scenario world", type_name::<V>())
+    }
+
+    pub(crate) fn clear(&self) {
+        // take the values out first, their drop may use the world
+        let values = core::mem::take(&mut *self.values.borrow_mut());
+        drop(values);
+    }
+}
+
+/// Value of the [`World`] borrowed by [`World::get`]
+pub struct WorldRef<V>(Rc<V>);
+
+impl<V> Deref for WorldRef<V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        &self.0
+    }
+}
+
+/// Value of the [`World`] borrowed by [`World::get_mut`], put back when dropped
+pub struct WorldRefMut<V: Any> {
+    values: Rc<RefCell<HashMap<TypeId, Entry>>>,
+    value: Option<V>,
+}
+
+impl<V: Any> Deref for WorldRefMut<V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        self.value.as_ref().expect("borrowed world value")
+    }
+}
+
+impl<V: Any> DerefMut for WorldRefMut<V> {
+    fn deref_mut(&mut self) -> &mut V {
+        self.value.as_mut().expect("borrowed world value")
+    }
+}
+
+impl<V: Any> Drop for WorldRefMut<V> {
+    fn drop(&mut self) {
+        let Some(value) = self.value.take() else {
+            return;
+        };
+        let mut values = self.values.borrow_mut();
+        // the world may have been emptied meanwhile, the value is then dropped
+        if let Some(entry @ Entry::Borrowed) = values.get_mut(&TypeId::of::<V>()) {
+            *entry = Entry::Stored(Rc::new(value));
+        } else {
+            drop(values);
+            drop(value);
+        }
+    }
+}
+
+/// Given step receiving the scenario [`World`]
+pub fn given_world<T, F>(s: impl Display, callback: F, w: impl IntoReporter) -> GivenContext<T>
+where
+    F: FnOnce(World) -> T,
+{
+    let mut scenario = Scenario::new(w);
+    let world = scenario.world();
+    let value = scenario.step(Keyword::Given, s, || callback(world));
+    GivenContext {
+        scenario,
+        value,
+        strictness: PhantomData,
+    }
+}
+
+/// Context whose next step receives the scenario [`World`] next to its input, created by `world`
+pub struct WithWorld<C> {
+    context: C,
+}
+
+/// `world` of a context and of its future, and the steps of their [`WithWorld`].
+/// Each step is `sync, async: Keyword => next context, next future`, with the strictness bound
+/// of the transition if it has one.
+macro_rules! impl_world_steps {
+    ($context:ident, $future:ident {
+        $($step:ident, $stepf:ident: $keyword:ident => $next:ident, $next_future:ident
+            $(where S: $transition:ident)?;)*
+    }) => {
+        impl<T, S> $context<T, S> {
+            /// Passes the scenario [`World`] to the next step
+            pub fn world(self) -> WithWorld<Self> {
+                WithWorld { context: self }
+            }
+        }
+
+        impl<T, S> WithWorld<$context<T, S>> {
+            $(
+                pub fn $step<Closure, EndType>(
+                    self,
+                    s: impl Display,
+                    fut: Closure,
+                ) -> $next<EndType, S>
+                where
+                    $(S: $transition,)?
+                    Closure: FnOnce(T, World) -> EndType,
+                {
+                    let $context {
+                        mut scenario,
+                        value,
+                        ..
+                    } = self.context;
+                    let world = scenario.world();
+                    let value = scenario.step(Keyword::$keyword, s, || fut(value, world));
+                    $next {
+                        scenario,
+                        value,
+                        strictness: PhantomData,
+                    }
+                }
+
+                pub async fn $stepf<Closure, Fut, EndType>(
+                    self,
+                    s: impl Display,
+                    fut: Closure,
+                ) -> $next<EndType, S>
+                where
+                    $(S: $transition,)?
+                    Closure: FnOnce(T, World) -> Fut,
+                    Fut: Future<Output = EndType>,
+                {
+                    let $context {
+                        mut scenario,
+                        value,
+                        ..
+                    } = self.context;
+                    let world = scenario.world();
+                    let value = scenario
+                        .stepf(Keyword::$keyword, s, || fut(value, world))
+                        .await;
+                    $next {
+                        scenario,
+                        value,
+                        strictness: PhantomData,
+                    }
+                }
+            )*
+        }
+
+        impl<T: 'static, S: 'static> $future<T, S> {
+            /// Passes the scenario [`World`] to the next step
+            pub fn world(self) -> WithWorld<Self> {
+                WithWorld { context: self }
+            }
+        }
+
+        impl<T: 'static, S: 'static> WithWorld<$future<T, S>> {
+            $(
+                pub fn $step<Closure, EndType>(
+                    self,
+                    s: impl Display + 'static,
+                    fut: Closure,
+                ) -> $next_future<EndType, S>
+                where
+                    $(S: $transition,)?
+                    EndType: 'static,
+                    Closure: FnOnce(T, World) -> EndType + 'static,
+                {
+                    let context = async { self.context.await.world().$step(s, fut) };
+                    $next_future {
+                        context: Box::pin(context),
+                    }
+                }
+
+                pub fn $stepf<Closure, Fut, EndType>(
+                    self,
+                    s: impl Display + 'static,
+                    fut: Closure,
+                ) -> $next_future<EndType, S>
+                where
+                    $(S: $transition,)?
+                    EndType: 'static,
+                    Closure: FnOnce(T, World) -> Fut + 'static,
+                    Fut: Future<Output = EndType> + 'static,
+                {
+                    let context = async { self.context.await.world().$stepf(s, fut).await };
+                    $next_future {
+                        context: Box::pin(context),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+impl_world_steps!(GivenContext, GivenContextFuture {
+    and, andf: And => GivenContext, GivenContextFuture;
+    when, whenf: When => WhenContext, WhenContextFuture;
+    then, thenf: Then => ThenContext, ThenContextFuture where S: ThenAfterGiven;
+});
+impl_world_steps!(WhenContext, WhenContextFuture {
+    when, whenf: When => WhenContext, WhenContextFuture;
+    and, andf: And => WhenContext, WhenContextFuture;
+    then, thenf: Then => ThenContext, ThenContextFuture;
+});
+impl_world_steps!(ThenContext, ThenContextFuture {
+    then, thenf: Then => ThenContext, ThenContextFuture;
+    and, andf: And => ThenContext, ThenContextFuture;
+    when, whenf: When => WhenContext, WhenContextFuture where S: WhenAfterThen;
+});
@@ -6,7 +6,16 @@
 //       And the account balance should be $80
 //       And the card should be returned
 
-use gnerkinf::{create_stdout_writer, given_data};
+use std::{
+    cell::Cell,
+    panic::{catch_unwind, AssertUnwindSafe},
+    rc::Rc,
+};
+
+use common::{CatchUnwind, SharedWriter};
+use gnerkinf::{create_stdout_writer, given_data, given_dataf, given_world, WhenContext, World};
+
+mod common;
 
 #[test]
 fn test_withdrawal() {
@@ -39,6 +48,346 @@ fn test_withdrawal() {
     .and("the card should be returned", |card| card.unwrap());
 }
 
+#[test]
+fn test_withdrawal_soft_assertions() {
+    let (money, account, card) = withdrawal_scenario(10000, 2000)
+        .then_soft("the ATM should dispense $20", |data| {
+            assert_eq!(data.0, 2000)
+        })
+        .and("the account balance should be $80", |data| {
+            assert_eq!(data.1.balance, 8000)
+        })
+        .and("the card should be returned", |data| {
+            assert!(data.2.is_some())
+        })
+        .into_value();
+    assert_eq!((money, account.balance, card.is_some()), (2000, 8000, true));
+}
+
+#[test]
+fn test_withdrawal_soft_assertions_report_every_failure() {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        withdrawal_scenario(1000, 2000)
+            .then_soft("the ATM should dispense $20", |data| {
+                assert_eq!(data.0, 2000, "dispensed")
+            })
+            .and("the account balance should be $80", |data| {
+                assert_eq!(data.1.balance, 8000, "balance")
+            })
+            .and("the card should be returned", |data| {
+                assert!(data.2.is_some(), "card")
+            });
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    let lines: Vec<&str> = message.lines().collect();
+    assert_eq!(lines[0], "2 soft assertions failed:");
+    assert!(lines[1].starts_with(
+        "Then the ATM should dispense $20: assertion `left == right` failed: dispensed"
+    ));
+    assert!(lines.iter().any(|line| line.starts_with(
+        "And the account balance should be $80: assertion `left == right` failed: balance"
+    )));
+    assert!(!message.contains("card"));
+}
+
+#[tokio::test]
+async fn test_withdrawal_async_soft_assertions() {
+    let result = CatchUnwind::new(async {
+        withdrawal_scenario(1000, 2000)
+            .then_soft("the ATM should dispense nothing", |data| {
+                assert_eq!(data.0, 0)
+            })
+            .thenf("the account balance should be $80", |data| async move {
+                assert_eq!(data.1.balance, 8000, "balance")
+            })
+            .await
+            .andf("the card should be returned", |data| async move {
+                assert!(data.2.is_some())
+            })
+            .await;
+    })
+    .await;
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(
+        message.starts_with("1 soft assertions failed:\nThen the account balance should be $80")
+    );
+}
+
+#[tokio::test]
+async fn test_withdrawal_soft_assertions_future() {
+    let result = CatchUnwind::new(async {
+        given_dataf(
+            "The account balance is $10",
+            Account { balance: 1000 },
+            create_stdout_writer(),
+        )
+        .whenf(
+            "the Account Holder requests $20",
+            |mut account| async move {
+                let money =
+                    Machine { money: 100000 }.request(&mut account, Card { valid: true }, 2000);
+                (money.0, account)
+            },
+        )
+        .then_soft("the ATM should dispense $20", |data| {
+            assert_eq!(data.0, 2000, "dispensed")
+        })
+        .andf("the account balance should be $0", |data| async move {
+            assert_eq!(data.1.balance, 0, "balance")
+        })
+        .and("the account balance should be $10", |data| {
+            assert_eq!(data.1.balance, 1000)
+        })
+        .await;
+    })
+    .await;
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    let lines: Vec<&str> = message.lines().collect();
+    assert_eq!(lines[0], "2 soft assertions failed:");
+    assert!(lines[1].starts_with("Then the ATM should dispense $20"));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("And the account balance should be $0")));
+}
+
+#[test]
+fn test_withdrawal_world() {
+    given_world(
+        "The account balance is $100",
+        |world| world.insert(Account { balance: 10000 }),
+        create_stdout_writer(),
+    )
+    .world()
+    .and("the card is valid", |_, world| {
+        world.insert(Card { valid: true })
+    })
+    .world()
+    .and("the machine contains enough money", |_, world| {
+        world.insert(Machine { money: 100000 })
+    })
+    .world()
+    .when("the Account Holder requests $20", |_, world| {
+        let (card, mut account) = (world.remove::<Card>(), world.remove::<Account>());
+        let (money, card) =
+            world
+                .get_mut::<Machine>()
+                .request(account.as_mut().unwrap(), card.unwrap(), 2000);
+        world.insert(account.unwrap());
+        world.insert(card);
+        money
+    })
+    .then("the ATM should dispense $20", |money| {
+        assert_eq!(money, 2000)
+    })
+    .world()
+    .and("the account balance should be $80", |(), world| {
+        assert_eq!(world.get::<Account>().balance, 8000)
+    })
+    .world()
+    .and("the card should be returned", |(), world| {
+        assert!(world.get::<Option<Card>>().is_some())
+    });
+}
+
+#[tokio::test]
+async fn test_withdrawal_world_async() {
+    withdrawal_scenario(10000, 2000)
+        .world()
+        .thenf(
+            "the receipt shows the new balance",
+            |data, world| async move {
+                world.insert(data.1.balance);
+                data
+            },
+        )
+        .await
+        .world()
+        .and("the receipt shows $80", |_, world| {
+            assert_eq!(*world.get::<u64>(), 8000)
+        });
+}
+
+#[test]
+fn test_world_missing_value() {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        given_data("no account", (), SharedWriter::default())
+            .world()
+            .when("the Account Holder checks the balance", |(), world| {
+                world.get::<Account>().balance
+            });
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(message, "no cash_withdrawal::Account in the scenario world");
+}
+
+#[test]
+fn test_world_emptied_at_scenario_end() {
+    struct Session(Rc<Cell<bool>>);
+
+    impl Drop for Session {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let closed = Rc::new(Cell::new(false));
+    let mut kept = None::<World>;
+    given_world(
+        "a logged in Account Holder",
+        |world| world.insert(Session(closed.clone())),
+        SharedWriter::default(),
+    )
+    .world()
+    .when("the Account Holder walks away", |_, world| {
+        kept = world.into();
+    })
+    .then("the session is still open", |()| assert!(!closed.get()));
+
+    assert!(closed.get());
+    assert!(!kept.unwrap().contains::<Session>());
+}
+
+#[test]
+fn test_world_values_borrowed_independently() {
+    given_world(
+        "The account balance is $100",
+        |world| {
+            world.insert(Account { balance: 10000 });
+            world.insert(Machine { money: 100000 })
+        },
+        SharedWriter::default(),
+    )
+    .world()
+    .when("the Account Holder requests $20", |_, world| {
+        let mut machine = world.get_mut::<Machine>();
+        let mut account = world.get_mut::<Account>();
+        let (money, card) = machine.request(&mut account, Card { valid: true }, 2000);
+        world.insert(card);
+        money
+    })
+    .world()
+    .then("the ATM should dispense $20", |money, world| {
+        assert_eq!(money, 2000);
+        assert_eq!(world.get::<Account>().balance, 8000);
+        assert_eq!(world.get::<Machine>().money, 98000);
+    });
+}
+
+#[test]
+fn test_world_value_borrowed_twice() {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        given_world(
+            "The account balance is $100",
+            |world| world.insert(Account { balance: 10000 }),
+            SharedWriter::default(),
+        )
+        .world()
+        .when("the Account Holder checks the balance twice", |_, world| {
+            let _account = world.get_mut::<Account>();
+            world.get::<Account>().balance
+        });
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(
+        message,
+        "cash_withdrawal::Account of the scenario world is already borrowed"
+    );
+}
+
+#[test]
+fn test_world_try_get_borrowed_value() {
+    given_world(
+        "The account balance is $100",
+        |world| world.insert(Account { balance: 10000 }),
+        SharedWriter::default(),
+    )
+    .world()
+    .when("the Account Holder checks the balance twice", |_, world| {
+        let account = world.get_mut::<Account>();
+        assert!(world.try_get::<Account>().is_none());
+        assert!(world.try_get_mut::<Account>().is_none());
+        drop(account);
+        let account = world.get::<Account>();
+        assert!(world.try_get_mut::<Account>().is_none());
+        assert_eq!(world.try_get::<Account>().unwrap().balance, account.balance);
+    });
+}
+
+#[test]
+fn test_world_insert_over_borrowed_value() {
+    given_world(
+        "The account balance is $100",
+        |world| world.insert(Account { balance: 10000 }),
+        SharedWriter::default(),
+    )
+    .world()
+    .when("the balance is replaced while it is read", |_, world| {
+        let account = world.get::<Account>();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            world.insert(Account { balance: 0 });
+        }));
+        assert!(result.is_err());
+        drop(account);
+        assert_eq!(world.get::<Account>().balance, 10000);
+    });
+}
+
+#[tokio::test]
+async fn test_withdrawal_world_future() {
+    given_dataf(
+        "The account balance is $100",
+        Account { balance: 10000 },
+        SharedWriter::default(),
+    )
+    .world()
+    .andf(
+        "the machine contains enough money",
+        |account, world| async move {
+            world.insert(Machine { money: 100000 });
+            account
+        },
+    )
+    .world()
+    .when("the Account Holder requests $20", |mut account, world| {
+        world
+            .get_mut::<Machine>()
+            .request(&mut account, Card { valid: true }, 2000);
+        account
+    })
+    .world()
+    .then("the machine has $980 left", |account, world| {
+        assert_eq!(account.balance, 8000);
+        assert_eq!(world.get::<Machine>().money, 98000);
+    })
+    .await;
+}
+
+fn withdrawal_scenario(balance: u64, request: u64) -> WhenContext<(u64, Account, Option<Card>)> {
+    given_data(
+        format!("The account balance is ${}", balance / 100),
+        Account { balance },
+        create_stdout_writer(),
+    )
+    .and("the card is valid", |account| {
+        (account, Card { valid: true })
+    })
+    .and("the machine contains enough money", |(account, card)| {
+        (account, card, Machine { money: 100000 })
+    })
+    .when(
+        format!("the Account Holder requests ${}", request / 100),
+        move |(mut account, card, mut machine)| {
+            let (money, card) = machine.request(&mut account, card, request);
+            (money, account, card)
+        },
+    )
+}
+
 struct Machine {
     money: u64,
 }
@@ -57,10 +406,12 @@ impl Machine {
     }
 }
 
+#[derive(Clone)]
 struct Account {
     balance: u64,
 }
 
+#[derive(Clone)]
 struct Card {
     valid: bool,
 }
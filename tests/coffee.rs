@@ -4,8 +4,18 @@
 // Then I should be served a coffee
 
 use core::num::NonZero;
+use std::{
+    cell::RefCell,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
 
-use gnerkinf::{create_stdout_writer, given_data, WhenContext};
+use common::SharedWriter;
+use gnerkinf::{
+    create_stdout_writer, given_data, outline, outline_pairwise, outline_product, Examples,
+    WhenContext,
+};
+
+mod common;
 
 #[test]
 fn test_coffee_machine() {
@@ -29,6 +39,232 @@ fn test_coffee_machine_not_enough_coffees() {
         });
 }
 
+#[test]
+fn test_coffee_machine_deposits() {
+    outline(
+        "a coffee for <deposit> cents",
+        Vec::new(),
+        create_stdout_writer(),
+    )
+    .examples(Examples::new("valid deposits", vec![100, 150]))
+    .examples(Examples::new("insufficient deposits", vec![10, 99]))
+    .map(|deposit: u64, _| {
+        coffee_machine_scenario(1, NonZero::new(deposit).unwrap())
+            .then("I should be served a coffee if I paid enough", |coffee| {
+                assert_eq!(coffee.is_some(), deposit >= 100)
+            });
+    });
+}
+
+#[test]
+fn test_coffee_machine_examples_headings() {
+    let writer = SharedWriter::default();
+    outline("a coffee for <deposit> cents", Vec::new(), writer.clone())
+        .examples(Examples::new("valid deposits", vec![100]).tag("@smoke"))
+        .examples(Examples::new("insufficient deposits", vec![10]).tag("@slow"))
+        .examples(Examples::new("refunds", vec![5]))
+        .skip_tagged("@slow")
+        .map(|deposit: u64, _| assert!(deposit > 0));
+
+    assert_eq!(
+        writer.output(),
+        "Scenario Outline: a coffee for <deposit> cents\n\
+         @smoke\n\
+         Examples: valid deposits\n\
+         Examples: refunds\n\
+         =========================\n"
+    );
+}
+
+#[test]
+fn test_coffee_machine_only_tagged_examples() {
+    let deposits = RefCell::new(Vec::new());
+    outline(
+        "a coffee for <deposit> cents",
+        vec![1],
+        SharedWriter::default(),
+    )
+    .examples(Examples::new("valid deposits", vec![100, 150]).tag("@smoke"))
+    .examples(Examples::new("insufficient deposits", vec![10]))
+    .only_tagged("@smoke")
+    .map(|deposit: u64, _| deposits.borrow_mut().push(deposit));
+    assert_eq!(deposits.into_inner(), [100, 150]);
+}
+
+#[test]
+fn test_coffee_machine_failed_examples() {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        outline(
+            "a coffee for <deposit> cents",
+            vec![100],
+            SharedWriter::default(),
+        )
+        .examples(Examples::new("valid deposits", vec![100, 50, 150]))
+        .examples(Examples::new("more deposits", vec![120, 10]))
+        .map(|deposit: u64, _| {
+            coffee_machine_scenario(1, NonZero::new(deposit).unwrap())
+                .then("I should be served a coffee", |coffee| {
+                    assert!(coffee.is_some(), "no coffee for {deposit} cents")
+                });
+        })
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    let lines: Vec<&str> = message.lines().collect();
+    assert_eq!(lines[0], "2 of 6 examples failed:");
+    assert!(lines[1].starts_with("examples \"valid deposits\" #2: "));
+    assert!(lines[1].contains("no coffee for 50 cents"));
+    assert!(lines[2].starts_with("examples \"more deposits\" #2: "));
+}
+
+#[test]
+fn test_coffee_machine_combinations() {
+    let writer = SharedWriter::default();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        outline_product(
+            "a coffee with <coffees> coffees left for <deposit> cents",
+            (vec![0, 1], vec![10, 100]),
+            writer.clone(),
+        )
+        .map(|(coffees, deposit): (u16, u64), _| {
+            coffee_machine_scenario(coffees, NonZero::new(deposit).unwrap())
+                .then("I should be served a coffee", |coffee| {
+                    assert!(coffee.is_some(), "no coffee")
+                });
+        })
+    }));
+
+    let headers: Vec<String> = writer
+        .output()
+        .lines()
+        .filter(|line| line.starts_with("  Example"))
+        .map(String::from)
+        .collect();
+    assert_eq!(
+        headers,
+        [
+            "  Example #1: coffees = 0, deposit = 10",
+            "  Example #2: coffees = 0, deposit = 100",
+            "  Example #3: coffees = 1, deposit = 10",
+            "  Example #4: coffees = 1, deposit = 100",
+        ]
+    );
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    let lines: Vec<&str> = message.lines().collect();
+    assert_eq!(lines[0], "3 of 4 examples failed:");
+    assert_eq!(
+        lines[1],
+        "example #1 (coffees = 0, deposit = 10): no coffee"
+    );
+    assert_eq!(
+        lines[2],
+        "example #2 (coffees = 0, deposit = 100): no coffee"
+    );
+    assert_eq!(
+        lines[3],
+        "example #3 (coffees = 1, deposit = 10): no coffee"
+    );
+}
+
+#[test]
+fn test_coffee_machine_pairwise_combinations() {
+    let rows = RefCell::new(Vec::new());
+    outline_pairwise(
+        "<coffees> coffees left, <deposit> cents and <sugar> sugars",
+        (vec![0, 1, 2], vec![10, 100, 150], vec![0, 1, 2]),
+        SharedWriter::default(),
+    )
+    .map(|row: (u16, u64, u8), _| rows.borrow_mut().push(row));
+
+    let rows = rows.into_inner();
+    assert!(rows.len() < 27, "{} rows", rows.len());
+    for coffees in [0, 1, 2] {
+        for deposit in [10, 100, 150] {
+            assert!(rows.iter().any(|row| (row.0, row.1) == (coffees, deposit)));
+        }
+        for sugar in [0, 1, 2] {
+            assert!(rows.iter().any(|row| (row.0, row.2) == (coffees, sugar)));
+        }
+    }
+    for deposit in [10, 100, 150] {
+        for sugar in [0, 1, 2] {
+            assert!(rows.iter().any(|row| (row.1, row.2) == (deposit, sugar)));
+        }
+    }
+}
+
+#[test]
+fn test_pairwise_combinations_of_many_values() {
+    let values: Vec<u8> = (0..10).collect();
+    let rows = RefCell::new(Vec::new());
+    outline_pairwise(
+        "<a>, <b>, <c>, <d> and <e>",
+        (
+            values.clone(),
+            values.clone(),
+            values.clone(),
+            values.clone(),
+            values.clone(),
+        ),
+        SharedWriter::default(),
+    )
+    .map(|row: (u8, u8, u8, u8, u8), _| {
+        rows.borrow_mut().push([row.0, row.1, row.2, row.3, row.4])
+    });
+
+    let rows = rows.into_inner();
+    assert!(rows.len() < 200, "{} rows", rows.len());
+    for first in 0..5 {
+        for second in first + 1..5 {
+            for a in 0..10 {
+                for b in 0..10 {
+                    assert!(rows.iter().any(|row| (row[first], row[second]) == (a, b)));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_coffee_machine_collected_change() {
+    let change = outline(
+        "the change for <deposit> cents",
+        vec![100, 120, 150],
+        SharedWriter::default(),
+    )
+    .collect(|deposit: u64, _| {
+        coffee_machine_scenario(1, NonZero::new(deposit).unwrap())
+            .then("I should be served a coffee", |coffee| {
+                assert!(coffee.is_some())
+            })
+            .and("I get my change back", |_| deposit - 100)
+            .into_value()
+    });
+    assert_eq!(change, [0, 20, 50]);
+    assert!(change.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+#[test]
+fn test_coffee_machine_collected_results() {
+    let results = outline(
+        "a coffee for <deposit> cents",
+        vec![100, 10],
+        SharedWriter::default(),
+    )
+    .try_collect(|deposit: u64, _| {
+        coffee_machine_scenario(1, NonZero::new(deposit).unwrap())
+            .then("I should be served a coffee", |coffee| {
+                assert!(coffee.is_some(), "no coffee for {deposit} cents");
+                deposit
+            })
+            .into_value()
+    });
+    assert_eq!(results[0], Ok(100));
+    let failure = results[1].as_ref().unwrap_err();
+    assert!(failure.starts_with("example #2: "), "{failure}");
+    assert!(failure.contains("no coffee for 10 cents"), "{failure}");
+}
+
 fn coffee_machine_scenario(coffees: u16, deposit: NonZero<u64>) -> WhenContext<Option<Coffee>> {
     given_data(
         format!("there are {coffees} coffees left in the machine"),
@@ -0,0 +1,58 @@
+use gnerkinf::{given, ColorChoice, TextReporter};
+
+use common::SharedWriter;
+
+mod common;
+
+#[test]
+fn test_colored_output() {
+    let writer = SharedWriter::default();
+    given(
+        "there are <coffees> coffees left in the machine",
+        || 1,
+        TextReporter::new(writer.clone()).color(ColorChoice::Always),
+    )
+    .when("I press the coffee button", |coffees| coffees - 1)
+    .then("there are no coffees left", |coffees| {
+        assert_eq!(coffees, 0)
+    });
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[0].starts_with("\x1b[32m\x1b[1mGiven\x1b[22m there are \x1b[4m<coffees>\x1b[24m"));
+    assert!(lines[1].starts_with("\x1b[32m\x1b[1mWhen\x1b[22m I press"));
+    assert!(lines[..3].iter().all(|line| line.ends_with("\x1b[0m")));
+}
+
+#[test]
+fn test_failed_step_is_red() {
+    let writer = SharedWriter::default();
+    let reporter = TextReporter::new(writer.clone()).color(ColorChoice::Always);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        given("there is 1 coffee", || 1, reporter)
+            .when("I press the coffee button twice", |coffees: u8| {
+                coffees.checked_sub(2)
+            })
+            .then("I should be served 2 coffees", |coffees| coffees.unwrap());
+    }));
+
+    assert!(result.is_err());
+    let output = writer.output();
+    let last = output.lines().rev().nth(1).unwrap();
+    assert!(last.starts_with("\x1b[31m\x1b[1mThen\x1b[22m I should be served 2 coffees"));
+    assert!(last.ends_with("\x1b[31m failed\x1b[0m"));
+}
+
+#[test]
+fn test_plain_output_without_color() {
+    let writer = SharedWriter::default();
+    given(
+        "there are <coffees> coffees left in the machine",
+        || 1,
+        TextReporter::new(writer.clone()).color(ColorChoice::Never),
+    )
+    .when("I look at the machine", |coffees| coffees)
+    .then("there is a coffee", |coffees| assert_eq!(coffees, 1));
+
+    assert!(!writer.output().contains('\x1b'));
+}
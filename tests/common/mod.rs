@@ -0,0 +1,57 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{
+    cell::RefCell,
+    io::Write,
+    panic::{catch_unwind, AssertUnwindSafe},
+    rc::Rc,
+};
+
+/// Writer that keeps everything written to it so tests can inspect the output
+#[derive(Clone, Default)]
+pub struct SharedWriter {
+    buffer: Rc<RefCell<Vec<u8>>>,
+}
+
+impl SharedWriter {
+    #[allow(dead_code)]
+    pub fn output(&self) -> String {
+        String::from_utf8(self.buffer.borrow().clone()).expect("utf8 output")
+    }
+}
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Resolves to the panic payload if the wrapped scenario future panics
+pub struct CatchUnwind<F>(Pin<Box<F>>);
+
+impl<F> CatchUnwind<F> {
+    #[allow(dead_code)]
+    pub fn new(future: F) -> Self {
+        Self(Box::pin(future))
+    }
+}
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = std::thread::Result<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = self.0.as_mut();
+        match catch_unwind(AssertUnwindSafe(|| future.poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(value)) => Poll::Ready(Ok(value)),
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
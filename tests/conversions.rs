@@ -1,7 +1,12 @@
+use std::{cell::Cell, rc::Rc};
+
+use common::SharedWriter;
 use gnerkinf::create_stdout_writer;
 use gnerkinf::givenf;
 use gnerkinf::outline;
-use gnerkinf::{given, given_data, WhenContext};
+use gnerkinf::{given, given_data, given_steps, WhenContext, WhenSteps};
+
+mod common;
 
 #[test]
 fn test_bdd_type_conversion_sync() {
@@ -197,6 +202,78 @@ async fn test_bdd_outline() {
     .await
 }
 
+#[tokio::test]
+async fn test_bdd_outline_examples() {
+    let data = vec![
+        (Car { wheels: 2 }, Bike { wheels: 2 }),
+        (Car { wheels: 4 }, Bike { wheels: 2 }),
+    ];
+    let expectations = [4, 6];
+    outline(
+        format!("{size} cars {size} bikes", size = data.len()),
+        data,
+        create_stdout_writer(),
+    )
+    .runf(|data, example| async move {
+        let index = example.index();
+        given_data(
+            format!(
+                "i have bike with {bike_wheels} wheels and a car with {car_wheels}",
+                car_wheels = data.0.wheels,
+                bike_wheels = data.1.wheels
+            ),
+            data,
+            example,
+        )
+        .whenf("i add transport wheels", |(bike, car)| async move {
+            Bike {
+                wheels: bike.wheels + car.wheels,
+            }
+        })
+        .await
+        .and("convert bike to car", |bike| Car {
+            wheels: bike.wheels,
+        })
+        .then(
+            format!(
+                "i should have {expected} wheels",
+                expected = expectations[index]
+            ),
+            |car| {
+                assert_eq!(car.wheels, expectations[index]);
+                car.wheels
+            },
+        )
+        .and(
+            format!("another {expected} wheels", expected = expectations[index]),
+            |wheels| {
+                assert_eq!(wheels, expectations[index]);
+            },
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_bdd_outline_collected_wheels() {
+    let data = vec![
+        (Car { wheels: 2 }, Bike { wheels: 2 }),
+        (Car { wheels: 4 }, Bike { wheels: 2 }),
+    ];
+    let wheels = outline("<car> and <bike> wheels", data, create_stdout_writer())
+        .collectf(|(car, bike), _| async move {
+            given_data("i have bike and a car", (car, bike), create_stdout_writer())
+                .whenf("i add transport wheels", |(car, bike)| async move {
+                    car.wheels + bike.wheels
+                })
+                .await
+                .then("i count the wheels", |wheels| wheels)
+                .into_value()
+        })
+        .await;
+    assert_eq!(wheels, [4, 6]);
+}
+
 #[tokio::test]
 async fn test_bdd_outline_as_loop() {
     let data = vec![
@@ -260,6 +337,53 @@ fn test_multiple_context2() {
         );
 }
 
+#[test]
+fn test_shared_steps() {
+    let expected = 8;
+    let prefix = some_context_steps();
+    prefix
+        .and("two more from the store", |bike| bike.add_wheels(2))
+        .then(
+            format!("i should have {expected} wheels"),
+            check_wheels(expected),
+        )
+        .run(create_stdout_writer());
+    prefix
+        .clone()
+        .run(create_stdout_writer())
+        .and("two more from the garage", |bike| bike.add_wheels(2))
+        .then(
+            format!("i should have {expected} wheels"),
+            check_wheels(expected),
+        );
+}
+
+#[test]
+fn test_steps_run_only_when_run() {
+    let writer = SharedWriter::default();
+    let bikes = Rc::new(Cell::new(0));
+    let produced = bikes.clone();
+    let steps = given_steps("i have bike", move || {
+        produced.set(produced.get() + 1);
+        produce_bike()
+    })
+    .when("i add spare wheels", |bike| bike.add_wheels(2))
+    .then("i should have 4 wheels", check_wheels(4));
+    assert_eq!(bikes.get(), 0);
+
+    steps.run(writer.clone());
+    steps.run(writer.clone());
+
+    assert_eq!(bikes.get(), 2);
+    let output = writer.output();
+    let steps: Vec<&str> = output
+        .lines()
+        .filter(|line| !line.starts_with('='))
+        .collect();
+    assert_eq!(steps.len(), 6);
+    assert!(steps[3].starts_with("Given i have bike ("));
+}
+
 struct Bike {
     wheels: u8,
 }
@@ -293,6 +417,13 @@ fn given_some_context() -> WhenContext<Bike> {
         .and("two more from the basement", |bike| bike.add_wheels(2))
 }
 
+fn some_context_steps() -> WhenSteps<Bike> {
+    given_steps("i have bike", produce_bike)
+        .and("spare two wheels", |bike| (bike, 2))
+        .when("i add spare wheels", |(bike, spare)| bike.add_wheels(spare))
+        .and("two more from the basement", |bike| bike.add_wheels(2))
+}
+
 fn check_wheels(wheels: u8) -> impl Fn(Bike) {
     move |bike| assert_eq!(bike.wheels, wheels)
 }
@@ -0,0 +1,110 @@
+#[gnerkinf::feature("tests/features/atm.feature")]
+mod atm {
+    use gnerkinf::{given, then, when, World};
+
+    #[given("the machine contains ${}")]
+    fn machine(world: &World, dollars: u64) {
+        world.insert(Machine {
+            money: dollars * 100,
+        });
+    }
+
+    #[given("the account balance is ${}")]
+    fn balance(world: &World, dollars: u64) {
+        world.insert(Account {
+            balance: dollars * 100,
+        });
+    }
+
+    #[given("the card is valid")]
+    fn valid_card(world: &World) {
+        world.insert(Card { valid: true });
+    }
+
+    #[given("the card is invalid")]
+    fn invalid_card(world: &World) {
+        world.insert(Card { valid: false });
+    }
+
+    #[when("the Account Holder requests ${}")]
+    async fn request(world: &World, dollars: u64) {
+        let card = world.remove::<Card>().unwrap();
+        let mut account = world.remove::<Account>().unwrap();
+        let (money, card) = world
+            .get_mut::<Machine>()
+            .request(&mut account, card, dollars * 100);
+        world.insert(account);
+        world.insert(card);
+        world.insert(Dispensed(money));
+    }
+
+    #[then("the ATM should dispense ${}")]
+    fn dispensed(world: &World, dollars: u64) {
+        assert_eq!(world.get::<Dispensed>().0, dollars * 100);
+    }
+
+    #[then("the account balance should be ${}")]
+    fn balance_after(world: &World, dollars: u64) {
+        assert_eq!(world.get::<Account>().balance, dollars * 100);
+    }
+
+    #[then("the card should be returned")]
+    fn card_returned(world: &World) {
+        assert!(world.get::<Option<Card>>().is_some());
+    }
+
+    struct Dispensed(u64);
+
+    struct Machine {
+        money: u64,
+    }
+
+    impl Machine {
+        fn request(
+            &mut self,
+            account: &mut Account,
+            card: Card,
+            money: u64,
+        ) -> (u64, Option<Card>) {
+            if !card.valid {
+                return (0, None);
+            }
+            if account.balance >= money && self.money >= money {
+                self.money -= money;
+                account.balance -= money;
+                return (money, card.into());
+            }
+            (0, card.into())
+        }
+    }
+
+    struct Account {
+        balance: u64,
+    }
+
+    struct Card {
+        valid: bool,
+    }
+}
+
+#[gnerkinf::feature("tests/features/kaffee.feature")]
+mod kaffee {
+    use gnerkinf::{given, then, when, World};
+
+    #[given("es sind {int} Kaffees übrig")]
+    fn coffees(world: &World, coffees: u16) {
+        world.insert(coffees);
+    }
+
+    #[when("ich den Knopf drücke")]
+    fn press(world: &mut World) {
+        *world.get_mut::<u16>() -= 1;
+    }
+
+    #[then("sind {int} Kaffees übrig")]
+    fn coffees_left(#[world] machine: &Maschine, coffees: u16) {
+        assert_eq!(*machine.get::<u16>(), coffees);
+    }
+
+    type Maschine = World;
+}
@@ -0,0 +1,31 @@
+Feature: Cash withdrawal
+  Account holders withdraw cash from the ATM with their card.
+
+  Background:
+    Given the machine contains $1000
+
+  Scenario: Withdraw a fixed amount
+    Given the account balance is $100
+    And the card is valid
+    When the Account Holder requests $20
+    Then the ATM should dispense $20
+    And the account balance should be $80
+    And the card should be returned
+
+  Scenario: Invalid card
+    Given the account balance is $100
+    But the card is invalid
+    When the Account Holder requests $20
+    Then the ATM should dispense $0
+    And the account balance should be $100
+
+  Scenario Outline: Withdraw varying amounts
+    Given the account balance is $<balance>
+    And the card is valid
+    When the Account Holder requests $<request>
+    Then the ATM should dispense $<dispensed>
+
+    Examples:
+      | balance | request | dispensed |
+      | 100     | 20      | 20        |
+      | 10      | 20      | 0         |
@@ -0,0 +1,23 @@
+Feature: Deposits
+  Cash and checks are credited to named accounts.
+
+  Background:
+    Given an account named "Daily Savings" with a balance of $10
+
+  Scenario: Deposit cents
+    When I have deposited 250 cents
+    Then the account balance is $12.50
+
+  Scenario: Deposit in another currency
+    When I deposit 10 EUR
+    Then the account balance is $21
+    And the account "Daily Savings" has 1 deposit
+
+  Scenario Outline: Deposit a check
+    When I deposit a check of $<amount>
+    Then the account balance is $<balance>
+
+    Examples:
+      | amount | balance |
+      | 5      | 15      |
+      | 0.25   | 10.25   |
@@ -0,0 +1,13 @@
+Feature: Checks
+
+  Scenario Outline: Deposit a check
+    Given an account named "Daily Savings" with a balance of $10
+    When I deposit a check of $<amount>
+    Then the account balance is $<balance>
+
+    Examples:
+      | amount | balance |
+      | 5      | 15      |
+
+  Scenaro: Deposit cents
+    When I have deposited 250 cents
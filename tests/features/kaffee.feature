@@ -0,0 +1,7 @@
+# language: de
+Funktionalität: Kaffeemaschine
+
+  Szenario: Kaffee holen
+    Angenommen es sind 2 Kaffees übrig
+    Wenn ich den Knopf drücke
+    Dann sind 1 Kaffees übrig
@@ -0,0 +1,8 @@
+Feature: Legacy deposits
+  Steps worded for the regex definitions of an older suite.
+
+  Scenario: Pay in dollars and cents
+    Given an account named "Daily Savings" with a balance of $10
+    When I pay in 3 dollars and 50 cents
+    Then I should have 13.50 dollars in the bank
+    And the account "Daily Savings" has 1 deposit
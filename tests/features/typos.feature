@@ -0,0 +1,5 @@
+Feature: Typos
+  Scenario: Misspelled step
+    Given an acount named "Daily Savings" with a balance of $10
+    When I have deposited 250 cents
+    Then the account balance is $12.50
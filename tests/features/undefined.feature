@@ -0,0 +1,10 @@
+Feature: Undefined steps
+  Scenario: Buy a coffee
+    Given there are 2 coffees left in the machine
+    And I have deposited 1.50 dollars
+    When I press the "espresso" button
+    Then I should be served a coffee
+
+  Scenario: Buy another coffee
+    Given there are 3 coffees left in the machine
+    When I press the "cappuccino" button
@@ -0,0 +1,169 @@
+use std::thread;
+
+use gnerkinf::{given, outline, titled, Format, KeywordCase, TextReporter};
+
+use common::SharedWriter;
+
+mod common;
+
+#[test]
+fn test_default_format() {
+    let writer = SharedWriter::default();
+    given("i have a bike", || 2, writer.clone())
+        .when("i add wheels", |wheels| wheels + 2)
+        .then("i should have 4 wheels", |wheels| assert_eq!(wheels, 4));
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("Given i have a bike ("));
+    assert_eq!(lines[3], "=========================");
+}
+
+#[test]
+fn test_pretty_format() {
+    let writer = SharedWriter::default();
+    given(
+        "i have a bike",
+        || 2,
+        TextReporter::new(writer.clone()).format(Format::pretty()),
+    )
+    .when("i add wheels", |wheels| wheels + 2)
+    .then("i should have 4 wheels", |wheels| assert_eq!(wheels, 4));
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "  Scenario: test_pretty_format");
+    assert!(lines[1].starts_with("    Given i have a bike ("));
+    assert!(lines[3].starts_with("    Then i should have 4 wheels ("));
+}
+
+#[test]
+fn test_titled_scenario() {
+    let writer = SharedWriter::default();
+    given(
+        "i have a bike",
+        || 2,
+        titled(
+            "Counting wheels",
+            TextReporter::new(writer.clone()).format(Format::pretty()),
+        ),
+    )
+    .when("i count the wheels", |wheels| assert_eq!(wheels, 2));
+
+    let output = writer.output();
+    assert_eq!(output.lines().next(), Some("  Scenario: Counting wheels"));
+}
+
+#[test]
+fn test_undetected_test_name() {
+    for name in [None, Some("main"), Some("tokio-runtime-worker")] {
+        let mut builder = thread::Builder::new();
+        if let Some(name) = name {
+            builder = builder.name(name.to_string());
+        }
+        let output = builder
+            .spawn(|| {
+                let writer = SharedWriter::default();
+                given(
+                    "i have a bike",
+                    || 2,
+                    TextReporter::new(writer.clone()).format(Format::pretty()),
+                )
+                .when("i count the wheels", |wheels| assert_eq!(wheels, 2));
+                writer.output()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert!(output.starts_with("    Given i have a bike ("), "{output}");
+    }
+}
+
+#[test]
+fn test_custom_format() {
+    let writer = SharedWriter::default();
+    let format = Format::default()
+        .separator(Some("---"))
+        .keyword_case(KeywordCase::Upper)
+        .numbered(true)
+        .indent(2);
+    given(
+        "i have a bike",
+        || 2,
+        TextReporter::new(writer.clone()).format(format),
+    )
+    .and("a spare wheel", |wheels| wheels + 1)
+    .when("i add wheels", |wheels| wheels + 2)
+    .then("i should have 5 wheels", |wheels| assert_eq!(wheels, 5));
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("  1. GIVEN i have a bike ("));
+    assert!(lines[1].starts_with("  2. AND a spare wheel ("));
+    assert!(lines[2].starts_with("  3. WHEN i add wheels ("));
+    assert!(lines[3].starts_with("  4. THEN i should have 5 wheels ("));
+    assert_eq!(lines[4], "---");
+}
+
+#[test]
+fn test_outline_format() {
+    let writer = SharedWriter::default();
+    outline(
+        "bikes with <wheels> wheels",
+        vec![2, 3],
+        TextReporter::new(writer.clone())
+            .format(Format::default().keyword_case(KeywordCase::Lower)),
+    )
+    .map(|wheels, _| assert!(wheels > 1));
+
+    assert_eq!(
+        writer.output(),
+        "scenario outline: bikes with <wheels> wheels\n=========================\n"
+    );
+}
+
+#[test]
+fn test_outline_example_sections() {
+    let writer = SharedWriter::default();
+    outline("bikes with <wheels> wheels", vec![2, 3], writer.clone()).run(|wheels, example| {
+        given("i have a bike", || wheels, example)
+            .when("i count its wheels", |wheels| wheels)
+            .then("it has wheels", |wheels| assert!(wheels > 1));
+    });
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 10);
+    assert_eq!(lines[0], "Scenario Outline: bikes with <wheels> wheels");
+    assert_eq!(lines[1], "  Example #1");
+    assert!(lines[2].starts_with("    Given i have a bike ("));
+    assert!(lines[4].starts_with("    Then it has wheels ("));
+    assert_eq!(lines[5], "  Example #2");
+    assert_eq!(lines[9], "=========================");
+}
+
+#[test]
+fn test_pretty_outline_example_sections() {
+    let writer = SharedWriter::default();
+    outline(
+        "bikes with <wheels> wheels",
+        vec![2],
+        TextReporter::new(writer.clone()).format(Format::pretty()),
+    )
+    .run(|wheels, example| {
+        given("i have a bike", || wheels, example)
+            .when("i count its wheels", |wheels| wheels)
+            .then("it has wheels", |wheels| assert!(wheels > 1));
+    });
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0], "  Scenario Outline: bikes with <wheels> wheels");
+    assert_eq!(lines[1], "    Example #1");
+    assert!(lines[2].starts_with("      Given i have a bike ("));
+}
@@ -0,0 +1,59 @@
+use gnerkinf::{given, outline, Format, Language, TextReporter, GERMAN, LITHUANIAN};
+
+use common::SharedWriter;
+
+mod common;
+
+#[test]
+fn test_lithuanian_keywords() {
+    let writer = SharedWriter::default();
+    given(
+        "yra 1 kava",
+        || 1,
+        TextReporter::new(writer.clone()).format(Format::pretty().language(&LITHUANIAN)),
+    )
+    .and("įmesta 1 euras", |coffees| coffees)
+    .when("paspaudžiu kavos mygtuką", |coffees| coffees - 1)
+    .then("gaunu kavą", |coffees| assert_eq!(coffees, 0));
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "  Scenarijus: test_lithuanian_keywords");
+    assert!(lines[1].starts_with("    Duota yra 1 kava"));
+    assert!(lines[2].starts_with("    Ir įmesta 1 euras"));
+    assert!(lines[3].starts_with("    Kai paspaudžiu"));
+    assert!(lines[4].starts_with("    Tada gaunu kavą"));
+}
+
+#[test]
+fn test_german_outline() {
+    let writer = SharedWriter::default();
+    outline(
+        "Kaffee mit <münzen>",
+        vec![1],
+        TextReporter::new(writer.clone()).format(Format::default().language(&GERMAN)),
+    )
+    .map(|coffees, _| assert_eq!(coffees, 1));
+
+    assert!(writer
+        .output()
+        .starts_with("Szenariogrundriss: Kaffee mit <münzen>\n"));
+}
+
+#[test]
+fn test_language_header() {
+    let feature = "# language: de\nFunktionalität: Kaffeemaschine\n";
+    assert_eq!(Language::from_header(feature), Some(&GERMAN));
+
+    let feature = "\n# a comment\n  #language:lt\nSavybė: Kavos aparatas\n";
+    assert_eq!(Language::from_header(feature), Some(&LITHUANIAN));
+
+    assert_eq!(
+        Language::from_header("Feature: coffee\n# language: de"),
+        None
+    );
+    assert_eq!(
+        Language::from_header("# language: xx\nFeature: coffee"),
+        None
+    );
+}
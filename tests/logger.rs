@@ -0,0 +1,74 @@
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    thread,
+};
+
+use common::SharedWriter;
+use gnerkinf::{create_failure_writer, create_stdout_writer, given, FailureWriter};
+
+mod common;
+
+#[test]
+fn test_steps_can_print_from_other_threads() {
+    given("i have a bike", || 2, create_stdout_writer())
+        .when("a mechanic reports from another thread", |wheels| {
+            thread::spawn(|| println!("mechanic: checking wheels"))
+                .join()
+                .unwrap();
+            wheels
+        })
+        .then("i should have 2 wheels", |wheels| assert_eq!(wheels, 2));
+}
+
+#[test]
+fn test_parallel_scenarios() {
+    let handles: Vec<_> = (0..4)
+        .map(|index| {
+            thread::spawn(move || {
+                given(format!("bike {index}"), || index, create_stdout_writer())
+                    .when("i add wheels", |wheels| wheels + 2)
+                    .then("i should have more wheels", move |wheels| {
+                        assert_eq!(wheels, index + 2)
+                    });
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn test_failure_writer_is_quiet_on_success() {
+    let writer = SharedWriter::default();
+    given("i have a bike", || 2, FailureWriter::new(writer.clone()))
+        .when("i add wheels", |wheels| wheels + 2)
+        .then("i should have 4 wheels", |wheels| assert_eq!(wheels, 4));
+
+    assert!(writer.output().is_empty());
+}
+
+#[test]
+fn test_failure_writer_prints_failed_scenario() {
+    let writer = SharedWriter::default();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        given("i have a bike", || 2, FailureWriter::new(writer.clone()))
+            .when("i add wheels", |wheels| wheels + 2)
+            .then("i should have 3 wheels", |wheels| assert_eq!(wheels, 3));
+    }));
+
+    assert!(result.is_err());
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("Given i have a bike"));
+    assert!(lines[2].starts_with("Then i should have 3 wheels"));
+    assert!(lines[2].ends_with("failed"));
+}
+
+#[test]
+fn test_stdout_failure_writer() {
+    given("i have a bike", || 2, create_failure_writer())
+        .when("i add wheels", |wheels| wheels + 2)
+        .then("i should have 4 wheels", |wheels| assert_eq!(wheels, 4));
+}
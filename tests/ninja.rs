@@ -4,10 +4,15 @@
 // Then one ninja dies (but not me)
 // And there is one ninja less alive
 
+use std::panic::{catch_unwind, panic_any, AssertUnwindSafe};
+
+use common::{CatchUnwind, SharedWriter};
 use gnerkinf::create_stdout_writer;
 use gnerkinf::given_data;
 use gnerkinf::given_dataf;
 
+mod common;
+
 #[test]
 fn test_ninja() {
     let mut ninjas = [
@@ -149,6 +154,259 @@ async fn test_ninja_last_async() {
     .await;
 }
 
+#[test]
+fn test_dead_ninja_cannot_fight() {
+    given_data(
+        "a dead ninja and a living ninja",
+        (Ninja { alive: false }, Ninja { alive: true }),
+        create_stdout_writer(),
+    )
+    .when_panics(
+        "the ninjas meet, they try to fight",
+        |(mut me, mut other)| me.fight(&mut other),
+    )
+    .then("the fight is refused", |caught| {
+        assert_eq!(caught.message(), "Ninjas must be alive to fight");
+    });
+}
+
+#[test]
+fn test_dead_ninja_cannot_fight_then_panics_with() {
+    given_data(
+        "a dead ninja and a living ninja",
+        (Ninja { alive: false }, Ninja { alive: true }),
+        create_stdout_writer(),
+    )
+    .when("the ninjas meet", |ninjas| ninjas)
+    .then_panics_with(
+        "they cannot fight",
+        "must be alive",
+        |(mut me, mut other)| me.fight(&mut other),
+    );
+}
+
+#[test]
+fn test_dead_ninja_cannot_fight_when_panics_with() {
+    given_data(
+        "a dead ninja and a living ninja",
+        (Ninja { alive: false }, Ninja { alive: true }),
+        create_stdout_writer(),
+    )
+    .when_panics_with(
+        "the ninjas meet, they try to fight",
+        "must be alive",
+        |(mut me, mut other)| me.fight(&mut other),
+    )
+    .then(
+        "the fight is refused",
+        |caught| assert!(caught.is::<&str>()),
+    );
+}
+
+#[tokio::test]
+async fn test_dead_ninja_cannot_fight_outside_panics_with() {
+    given_data(
+        "a dead ninja and a living ninja",
+        (Ninja { alive: false }, Ninja { alive: true }),
+        create_stdout_writer(),
+    )
+    .when_panics_withf(
+        "the ninjas meet outside, they try to fight",
+        "must be alive",
+        |(mut me, mut other)| async move { me.fight_outside(&mut other).await },
+    )
+    .await
+    .when("the ninjas meet again", |_| {
+        (Ninja { alive: false }, Ninja { alive: true })
+    })
+    .then_panics_withf(
+        "they cannot fight outside",
+        "must be alive",
+        |(mut me, mut other)| async move { me.fight_outside(&mut other).await },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_expected_async_panic_message_mismatch() {
+    let result = CatchUnwind::new(async {
+        given_data(
+            "a dead ninja and a living ninja",
+            (Ninja { alive: false }, Ninja { alive: true }),
+            SharedWriter::default(),
+        )
+        .when_panics_withf(
+            "the ninjas meet outside, they try to fight",
+            "must be asleep",
+            |(mut me, mut other)| async move { me.fight_outside(&mut other).await },
+        )
+        .await;
+    })
+    .await;
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(
+        message,
+        "When the ninjas meet outside, they try to fight: expected a panic with \"must be asleep\", \
+         got \"Ninjas must be alive to fight\""
+    );
+}
+
+#[tokio::test]
+async fn test_dead_ninja_cannot_fight_outside() {
+    given_data(
+        "a dead ninja and a living ninja",
+        (Ninja { alive: false }, Ninja { alive: true }),
+        create_stdout_writer(),
+    )
+    .when_panicsf(
+        "the ninjas meet outside, they try to fight",
+        |(mut me, mut other)| async move { me.fight_outside(&mut other).await },
+    )
+    .await
+    .then("the fight is refused", |caught| {
+        assert_eq!(caught.message(), "Ninjas must be alive to fight");
+    });
+}
+
+#[tokio::test]
+async fn test_dead_ninja_cannot_fight_in_async_chain() {
+    given_dataf(
+        "a dead ninja and a living ninja",
+        (Ninja { alive: false }, Ninja { alive: true }),
+        create_stdout_writer(),
+    )
+    .when_panics(
+        "the ninjas meet, they try to fight",
+        |(mut me, mut other)| me.fight(&mut other),
+    )
+    .then("the fight is refused", |caught| {
+        assert_eq!(caught.message(), "Ninjas must be alive to fight");
+    })
+    .await;
+
+    given_dataf(
+        "a dead ninja and a living ninja",
+        (Ninja { alive: false }, Ninja { alive: true }),
+        create_stdout_writer(),
+    )
+    .when_panics_withf(
+        "the ninjas meet outside, they try to fight",
+        "must be alive",
+        |(mut me, mut other)| async move { me.fight_outside(&mut other).await },
+    )
+    .and("the ninjas meet again", |_| {
+        (Ninja { alive: false }, Ninja { alive: true })
+    })
+    .then_panics_with(
+        "they cannot fight",
+        "must be alive",
+        |(mut me, mut other)| me.fight(&mut other),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_dead_ninja_cannot_fight_after_then() {
+    given_data(
+        "a dead ninja and a living ninja",
+        (Ninja { alive: false }, Ninja { alive: true }),
+        create_stdout_writer(),
+    )
+    .when("the ninjas meet", |ninjas| ninjas)
+    .then("one ninja is dead", |ninjas| {
+        assert!(!ninjas.0.alive);
+        ninjas
+    })
+    .then_panics_with(
+        "they cannot fight",
+        "must be alive",
+        |(mut me, mut other)| me.fight(&mut other),
+    )
+    .then_panics("the dead ninja cannot fight either", |_| {
+        Ninja { alive: false }.fight(&mut Ninja { alive: true })
+    });
+
+    given_dataf(
+        "a dead ninja and a living ninja",
+        (Ninja { alive: false }, Ninja { alive: true }),
+        create_stdout_writer(),
+    )
+    .when("the ninjas meet", |ninjas| ninjas)
+    .then("one ninja is dead", |ninjas| {
+        assert!(!ninjas.0.alive);
+        ninjas
+    })
+    .then_panicsf(
+        "they cannot fight outside",
+        |(mut me, mut other)| async move { me.fight_outside(&mut other).await },
+    )
+    .and("the fight is refused", |caught| {
+        assert_eq!(caught.message(), "Ninjas must be alive to fight");
+    })
+    .await;
+}
+
+#[test]
+fn test_expected_panic_missing() {
+    let writer = SharedWriter::default();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        given_data(
+            "two living ninjas",
+            (Ninja { alive: true }, Ninja { alive: true }),
+            writer.clone(),
+        )
+        .when_panics(
+            "the ninjas meet, they try to fight",
+            |(mut me, mut other)| me.fight(&mut other),
+        );
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(
+        message,
+        "When the ninjas meet, they try to fight: expected a panic"
+    );
+    let output = writer.output();
+    assert!(output.lines().nth(1).unwrap().ends_with(") failed"));
+}
+
+#[test]
+fn test_expected_panic_message_mismatch() {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        given_data(
+            "a dead ninja and a living ninja",
+            (Ninja { alive: false }, Ninja { alive: true }),
+            SharedWriter::default(),
+        )
+        .when("the ninjas meet", |ninjas| ninjas)
+        .then_panics_with(
+            "they cannot fight",
+            "must be asleep",
+            |(mut me, mut other)| me.fight(&mut other),
+        );
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(
+        message,
+        "Then they cannot fight: expected a panic with \"must be asleep\", \
+         got \"Ninjas must be alive to fight\""
+    );
+}
+
+#[test]
+fn test_panic_payload_type() {
+    struct Surrender(u8);
+
+    given_data("a cornered ninja", 3, create_stdout_writer())
+        .when_panics("the ninja gives up", |wounds| panic_any(Surrender(wounds)))
+        .then("the surrender carries the wounds", |caught| {
+            assert_eq!(caught.message(), "non string panic payload");
+            assert_eq!(caught.downcast_ref::<Surrender>().unwrap().0, 3);
+        });
+}
+
 struct Ninja {
     alive: bool,
 }
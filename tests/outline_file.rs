@@ -0,0 +1,150 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use common::SharedWriter;
+use gnerkinf::{given, outline_file, ExampleRow, FromRow};
+
+mod common;
+
+struct Purchase {
+    deposit: u64,
+    coffees: u16,
+    served: bool,
+}
+
+impl FromRow for Purchase {
+    fn from_row(row: &ExampleRow) -> Result<Self, String> {
+        Ok(Self {
+            deposit: row.get("deposit")?,
+            coffees: row.get("coffees")?,
+            served: row.get("served")?,
+        })
+    }
+}
+
+fn buy_coffee(purchase: Purchase, _: usize) {
+    given(
+        format!("there are {} coffees left", purchase.coffees),
+        || purchase.coffees,
+        SharedWriter::default(),
+    )
+    .when(format!("I deposit {} cents", purchase.deposit), |coffees| {
+        coffees > 0 && purchase.deposit >= 100
+    })
+    .then(
+        format!("I should be served a coffee: {}", purchase.served),
+        |served| assert_eq!(served, purchase.served, "deposit {}", purchase.deposit),
+    );
+}
+
+fn failure_lines(path: &str) -> Vec<String> {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        outline_file("buying coffee", path, SharedWriter::default()).map(buy_coffee)
+    }));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    message.lines().map(str::to_string).collect()
+}
+
+#[test]
+fn test_csv_examples() {
+    let lines = failure_lines("tests/examples/deposits.csv");
+    assert_eq!(lines[0], "1 of 4 examples failed:");
+    assert!(lines[1].starts_with("tests/examples/deposits.csv:5: "));
+}
+
+#[test]
+fn test_csv_quoted_cells() {
+    let notes = std::cell::RefCell::new(Vec::new());
+    outline_file(
+        "notes",
+        "tests/examples/deposits.csv",
+        SharedWriter::default(),
+    )
+    .map(|row: ExampleRow, _| {
+        notes
+            .borrow_mut()
+            .push(row.text("note").unwrap().to_string())
+    });
+    assert_eq!(
+        notes.into_inner(),
+        [
+            "exact amount",
+            "change, please",
+            "not \"enough\"",
+            "sold out"
+        ]
+    );
+}
+
+#[test]
+fn test_csv_spaced_cells() {
+    let rows = std::cell::RefCell::new(Vec::new());
+    outline_file(
+        "notes",
+        "tests/examples/spaced.csv",
+        SharedWriter::default(),
+    )
+    .map(|row: ExampleRow, _| {
+        rows.borrow_mut().push((
+            row.get::<u64>("deposit").unwrap(),
+            row.text("note").unwrap().to_string(),
+        ))
+    });
+    assert_eq!(
+        rows.into_inner(),
+        [(1, "2".to_string()), (150, " change, please ".to_string())]
+    );
+}
+
+#[test]
+#[should_panic(expected = "tests/examples/malformed.csv:2: unexpected text after closing quote")]
+fn test_csv_text_after_closing_quote() {
+    outline_file(
+        "deposits",
+        "tests/examples/malformed.csv",
+        SharedWriter::default(),
+    )
+    .map(|_: ExampleRow, _| {});
+}
+
+#[test]
+fn test_csv_examples_heading() {
+    let writer = SharedWriter::default();
+    outline_file("notes", "tests/examples/deposits.csv", writer.clone())
+        .map(|row: ExampleRow, _| assert_eq!(row.columns().len(), 4));
+    assert!(writer
+        .output()
+        .starts_with("Scenario Outline: notes\nExamples: deposits.csv\n"));
+}
+
+#[test]
+#[should_panic(expected = "tests/examples/deposits.csv:2: no column `price`")]
+fn test_missing_column() {
+    struct Price(#[allow(dead_code)] u64);
+    impl FromRow for Price {
+        fn from_row(row: &ExampleRow) -> Result<Self, String> {
+            row.get("price").map(Price)
+        }
+    }
+    outline_file(
+        "prices",
+        "tests/examples/deposits.csv",
+        SharedWriter::default(),
+    )
+    .map(|_: Price, _| {});
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_examples() {
+    let lines = failure_lines("tests/examples/deposits.json");
+    assert_eq!(lines[0], "1 of 3 examples failed:");
+    assert!(lines[1].starts_with("tests/examples/deposits.json:4: "));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_examples() {
+    let lines = failure_lines("tests/examples/deposits.toml");
+    assert_eq!(lines[0], "1 of 2 examples failed:");
+    assert!(lines[1].starts_with("tests/examples/deposits.toml:6: "));
+}
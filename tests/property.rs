@@ -0,0 +1,136 @@
+use std::{
+    cell::RefCell,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use common::{CatchUnwind, SharedWriter};
+use gnerkinf::{given, integers, property, strings, vecs, Generator, Rng};
+
+mod common;
+
+/// Serves a coffee for 100 cents but can't give change for more than 250
+fn buy_coffee(deposit: u64, coffees: u16) {
+    given(
+        format!("there are {coffees} coffees left"),
+        || coffees,
+        SharedWriter::default(),
+    )
+    .when(format!("I deposit {deposit} cents"), |coffees| {
+        assert!(deposit <= 250, "no change for {deposit} cents");
+        coffees > 0 && deposit >= 100
+    })
+    .then("I am served a coffee if I paid enough", |served| {
+        assert_eq!(served, coffees > 0 && deposit >= 100)
+    });
+}
+
+fn failure(run: impl FnOnce()) -> String {
+    let result = catch_unwind(AssertUnwindSafe(run));
+    *result.unwrap_err().downcast::<String>().unwrap()
+}
+
+#[test]
+fn test_property_passes() {
+    let writer = SharedWriter::default();
+    property(
+        "a coffee for <deposit> cents with <coffees> coffees",
+        (integers(0..=250u64), integers(0..=5u16)),
+        writer.clone(),
+    )
+    .seed(42)
+    .cases(50)
+    .map(|(deposit, coffees), _| buy_coffee(deposit, coffees));
+
+    assert!(writer.output().starts_with(
+        "Scenario Outline: a coffee for <deposit> cents with <coffees> coffees (seed 42)\n"
+    ));
+}
+
+#[test]
+fn test_property_shrinks_failing_example() {
+    let message = failure(|| {
+        property(
+            "a coffee for <deposit> cents with <coffees> coffees",
+            (integers(0..=1000u64), integers(0..=5u16)),
+            SharedWriter::default(),
+        )
+        .seed(7)
+        .map(|(deposit, coffees), _| buy_coffee(deposit, coffees))
+    });
+
+    let lines: Vec<&str> = message.lines().collect();
+    assert!(lines[0].starts_with("property failed on case #"));
+    assert!(lines[0].ends_with("with seed 7"));
+    assert_eq!(lines[1], "minimal failing example:");
+    assert_eq!(lines[2], "Examples:");
+    assert_eq!(lines[3], "  | deposit | coffees |");
+    assert_eq!(lines[4], "  | 251     | 0       |");
+}
+
+#[test]
+fn test_property_random_seed_rerun() {
+    let message = failure(|| {
+        property("<deposit>", integers(0..=1000u64), SharedWriter::default())
+            .map(|deposit, _| buy_coffee(deposit, 1))
+    });
+
+    let first_line = message.lines().next().unwrap();
+    let (_, seed) = first_line.split_once(" with seed ").unwrap();
+    let (seed, rerun) = seed.split_once(", ").unwrap();
+    assert_eq!(rerun, format!("rerun it with GNERKINF_SEED={seed}"));
+}
+
+#[test]
+fn test_property_shrinks_collections() {
+    let message = failure(|| {
+        property(
+            "sevens",
+            vecs(integers(0..=9u8), 0..=10),
+            SharedWriter::default(),
+        )
+        .seed(1)
+        .map(|digits, _| assert!(!digits.contains(&7)))
+    });
+    assert!(message.contains("\n  | value 1 |\n  | [7]     |\n"));
+
+    let message = failure(|| {
+        property("<word>", strings("ab", 0..=8), SharedWriter::default())
+            .seed(1)
+            .map(|word, _| assert!(!word.contains('b')))
+    });
+    assert!(message.contains("\n  | word |\n  | \"b\"  |\n"));
+}
+
+#[test]
+fn test_property_is_deterministic() {
+    let generate = || {
+        let values = RefCell::new(Vec::new());
+        property("values", integers(-1000..=1000i32), SharedWriter::default())
+            .seed(3)
+            .cases(20)
+            .map(|value, _| values.borrow_mut().push(value));
+        values.into_inner()
+    };
+    assert_eq!(generate(), generate());
+
+    let mut rng = Rng::new(3);
+    let generator = integers(-5..=5i8);
+    assert!((0..100).all(|_| (-5..=5).contains(&generator.generate(&mut rng))));
+}
+
+#[tokio::test]
+async fn test_property_async() {
+    let result = CatchUnwind::new(
+        property(
+            "a coffee for <deposit> cents",
+            integers(0..=1000u64),
+            SharedWriter::default(),
+        )
+        .seed(11)
+        .mapf(|deposit, _| async move { buy_coffee(deposit, 1) }),
+    )
+    .await;
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("\n  | deposit |\n  | 251     |\n"));
+}
@@ -0,0 +1,273 @@
+use core::str::FromStr;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use common::SharedWriter;
+use gnerkinf::{Parameter, SnippetStyle, StepRegistry, World};
+
+mod common;
+
+struct Account {
+    name: String,
+    cents: u64,
+    deposits: usize,
+}
+
+impl Account {
+    fn deposit(&mut self, cents: u64) {
+        self.cents += cents;
+        self.deposits += 1;
+    }
+}
+
+enum Currency {
+    Dollar,
+    Euro,
+}
+
+impl Parameter for Currency {
+    const NAME: &'static str = "currency";
+    const REGEX: &'static str = "USD|EUR";
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "USD" => Ok(Self::Dollar),
+            "EUR" => Ok(Self::Euro),
+            other => Err(format!("unknown currency {other}")),
+        }
+    }
+}
+
+fn bank() -> StepRegistry {
+    StepRegistry::new()
+        .parameter::<Currency>()
+        .given(
+            "an account named {string} with a balance of ${int}",
+            |world: &World, name: String, dollars: u64| {
+                world.insert(Account {
+                    name,
+                    cents: dollars * 100,
+                    deposits: 0,
+                });
+            },
+        )
+        .when(
+            "I have deposited {int} cent(s)",
+            |world: &World, cents: u64| world.get_mut::<Account>().deposit(cents),
+        )
+        .when(
+            "I deposit {int} {currency}",
+            |world: &World, amount: u64, currency: Currency| {
+                let cents = match currency {
+                    Currency::Dollar => amount * 100,
+                    Currency::Euro => amount * 110,
+                };
+                world.get_mut::<Account>().deposit(cents)
+            },
+        )
+        .when(
+            "I deposit a check/cheque of ${float}",
+            |world: &World, dollars: f64| {
+                world
+                    .get_mut::<Account>()
+                    .deposit((dollars * 100.0).round() as u64)
+            },
+        )
+        .then(
+            "the account balance is ${float}",
+            |world: &World, dollars: f64| {
+                assert_eq!(
+                    world.get::<Account>().cents,
+                    (dollars * 100.0).round() as u64
+                )
+            },
+        )
+        .then(
+            "the account {string} has {int} deposit(s)",
+            |world: &World, name: String, deposits: usize| {
+                let account = world.get::<Account>();
+                assert_eq!(account.name, name);
+                assert_eq!(account.deposits, deposits);
+            },
+        )
+}
+
+#[test]
+fn test_registry_runs_feature() {
+    let writer = SharedWriter::default();
+    bank().run_feature_with("tests/features/deposits.feature", || writer.clone());
+
+    let output = writer.output();
+    assert!(output.contains("Given an account named \"Daily Savings\" with a balance of $10 ("));
+    assert!(output.contains("When I deposit a check of $0.25 ("));
+    assert!(output.contains("And the account \"Daily Savings\" has 1 deposit ("));
+}
+
+#[test]
+fn test_undefined_step_suggests_definition() {
+    let writer = SharedWriter::default();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        bank().run_feature_with("tests/features/typos.feature", || writer.clone())
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(
+        message,
+        "1 of 1 scenarios failed:\n\
+         tests/features/typos.feature:3: Misspelled step: \
+         undefined step `Given an acount named \"Daily Savings\" with a balance of $10`, \
+         did you mean `an account named {string} with a balance of ${int}`?"
+    );
+}
+
+#[test]
+fn test_ambiguous_step() {
+    let registry = bank().step(
+        "I have deposited {int} {word}",
+        |_: &World, _: u64, _: String| {},
+    );
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        registry.run_feature_with("tests/features/deposits.feature", SharedWriter::default)
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(
+        message,
+        "1 of 4 scenarios failed:\n\
+         tests/features/deposits.feature:8: Deposit cents: \
+         ambiguous step `When I have deposited 250 cents` matches \
+         `I have deposited {int} cent(s)`, `I have deposited {int} {word}`"
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "tests/features/examples_typo.feature:12: unexpected line `Scenaro: Deposit cents`"
+)]
+fn test_mistyped_line_after_examples() {
+    bank().run_feature_with(
+        "tests/features/examples_typo.feature",
+        SharedWriter::default,
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "invalid step expression `the balance is {money}`: undefined parameter type `{money}`"
+)]
+fn test_undefined_parameter_type() {
+    StepRegistry::new().then("the balance is {money}", |_: &World, _: u64| {});
+}
+
+#[test]
+#[should_panic(
+    expected = "invalid step expression `I have {int coffees`: unterminated parameter `{int coffees`"
+)]
+fn test_unterminated_parameter() {
+    StepRegistry::new().given("I have {int coffees", |_: &World, _: u64| {});
+}
+
+#[test]
+#[should_panic(
+    expected = "step expression `I deposit {int} cents` has 1 parameters but its step takes 2 arguments"
+)]
+fn test_parameter_count_mismatch() {
+    StepRegistry::new().when("I deposit {int} cents", |_: &World, _: u64, _: u64| {});
+}
+
+#[test]
+fn test_regex_steps() {
+    let writer = SharedWriter::default();
+    bank()
+        .when_regex(
+            r"^I pay in (\d+) dollars? and (\d+) cents?$",
+            |world: &World, dollars: u64, cents: u64| {
+                world.get_mut::<Account>().deposit(dollars * 100 + cents)
+            },
+        )
+        .then_regex(
+            r"should have ([\d.]+) dollars",
+            |world: &World, dollars: f64| {
+                assert_eq!(
+                    world.get::<Account>().cents,
+                    (dollars * 100.0).round() as u64
+                )
+            },
+        )
+        .run_feature_with("tests/features/legacy.feature", || writer.clone());
+
+    let output = writer.output();
+    assert!(output.contains("When I pay in 3 dollars and 50 cents ("));
+    assert!(output.contains("Then I should have 13.50 dollars in the bank ("));
+}
+
+#[test]
+#[should_panic(
+    expected = "step expression `/^I pay in (\\d+) dollars?$/` has 1 parameters but its step takes 0 arguments"
+)]
+fn test_regex_capture_count_mismatch() {
+    StepRegistry::new().when_regex(r"^I pay in (\d+) dollars?$", |_: &World| {});
+}
+
+#[test]
+fn test_undefined_step_snippets() {
+    let file = std::env::temp_dir().join("gnerkinf_registry_snippets.rs");
+    let registry = StepRegistry::new()
+        .then("I should be served a coffee", |_: &World| {})
+        .snippets_file(&file);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        registry.run_feature_with("tests/features/undefined.feature", SharedWriter::default)
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(
+        std::fs::read_to_string(&file).unwrap(),
+        r#".given("there are {int} coffees left in the machine", |world: &World, int: i64| {
+    todo!()
+})
+
+.given("I have deposited {float} dollars", |world: &World, float: f64| {
+    todo!()
+})
+
+.when("I press the {string} button", |world: &World, string: String| {
+    todo!()
+})
+"#
+    );
+}
+
+#[test]
+fn test_chaining_snippets() {
+    let file = std::env::temp_dir().join("gnerkinf_chaining_snippets.rs");
+    let registry = StepRegistry::new()
+        .snippets(SnippetStyle::Chaining)
+        .snippets_file(&file);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        registry.run_feature_with("tests/features/undefined.feature", SharedWriter::default)
+    }));
+
+    assert!(result.is_err());
+    let snippets = std::fs::read_to_string(&file).unwrap();
+    assert!(snippets.starts_with(
+        r#"given("there are 2 coffees left in the machine", || {
+    todo!()
+}, create_stdout_writer())
+
+.and("I have deposited 1.50 dollars", |state| {
+    todo!()
+})
+
+.when("I press the \"espresso\" button", |state| {
+    todo!()
+})
+
+.then("I should be served a coffee", |state| {
+    todo!()
+})
+"#
+    ));
+}
@@ -0,0 +1,146 @@
+use core::{cell::Cell, time::Duration};
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    rc::Rc,
+    time::Instant,
+};
+
+use common::SharedWriter;
+use gnerkinf::{given_data, given_dataf, retry_scenario, retry_scenariof, Backoff, Retry};
+
+mod common;
+
+#[test]
+fn test_step_retried_until_it_passes() {
+    let writer = SharedWriter::default();
+    let calls = Cell::new(0);
+    given_data("a coffee machine", 1, writer.clone())
+        .retry(3, Backoff::Fixed(Duration::from_millis(1)))
+        .when("I press the coffee button", |coffees| {
+            calls.set(calls.get() + 1);
+            assert!(calls.get() > 2, "machine is warming up");
+            coffees - 1
+        })
+        .then("there are no coffees left", |coffees| {
+            assert_eq!(coffees, 0)
+        });
+
+    assert_eq!(calls.get(), 3);
+    let output = writer.output();
+    let when = output.lines().nth(1).unwrap();
+    assert!(when.starts_with("When I press the coffee button ("));
+    assert!(when.ends_with(", 3 attempts)"));
+}
+
+#[test]
+fn test_step_retries_exhausted() {
+    let writer = SharedWriter::default();
+    let calls = Cell::new(0);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        given_data("a coffee machine", 1, writer.clone())
+            .retry(2, Backoff::None)
+            .when("I press the coffee button", |coffees: u8| {
+                calls.set(calls.get() + 1);
+                coffees.checked_sub(2).expect("machine is broken")
+            });
+    }));
+
+    assert_eq!(calls.get(), 2);
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(message, "machine is broken");
+    let output = writer.output();
+    let when = output.lines().nth(1).unwrap();
+    assert!(when.ends_with(", 2 attempts) failed"));
+}
+
+#[tokio::test]
+async fn test_eventually_consistent_then() {
+    let started = Instant::now();
+    given_data("an order is placed", started, SharedWriter::default())
+        .when("the order is processed", |started| started)
+        .eventually(
+            Duration::from_secs(5),
+            Backoff::Exponential {
+                initial: Duration::from_millis(5),
+                max: Duration::from_millis(20),
+            },
+        )
+        .thenf("the order shows up in the history", |started| async move {
+            assert!(started.elapsed() >= Duration::from_millis(30));
+        })
+        .await;
+}
+
+#[test]
+fn test_flaky_scenario_retried() {
+    let writer = SharedWriter::default();
+    retry_scenario(
+        Retry::attempts(3, Backoff::None),
+        writer.clone(),
+        |attempt| {
+            let number = attempt.number();
+            given_data("a flaky service", number, attempt)
+                .when("I call it", |number| number)
+                .then("it responds", |number| assert!(number > 1, "timeout"));
+        },
+    );
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[3], "Scenario attempt 1 failed, retrying: timeout");
+    assert!(lines[4].starts_with("Given a flaky service ("));
+    assert_eq!(lines[7], "Scenario passed after 2 attempts");
+    assert_eq!(lines.len(), 9);
+}
+
+#[tokio::test]
+async fn test_flaky_async_scenario_gives_up() {
+    let attempts = Cell::new(0);
+    let writer = SharedWriter::default();
+    let result = common::CatchUnwind::new(retry_scenariof(
+        Retry::eventually(
+            Duration::from_millis(50),
+            Backoff::Fixed(Duration::from_millis(20)),
+        ),
+        writer.clone(),
+        |attempt| async {
+            attempts.set(attempts.get() + 1);
+            given_data("a broken service", (), attempt)
+                .whenf("I call it", |()| async { panic!("unavailable") })
+                .await;
+        },
+    ))
+    .await;
+
+    assert!(result.is_err());
+    assert!(attempts.get() >= 2);
+    let output = writer.output();
+    assert!(output.contains("Scenario attempt 1 failed, retrying: unavailable"));
+    assert!(!output.contains("passed after"));
+}
+
+#[tokio::test]
+async fn test_async_chain_step_retried() {
+    let calls = Rc::new(Cell::new(0));
+    let writer = SharedWriter::default();
+    let counter = calls.clone();
+    given_dataf("a coffee machine", 1, writer.clone())
+        .retry(3, Backoff::None)
+        .whenf("I press the coffee button", move |coffees| {
+            let counter = counter.clone();
+            async move {
+                counter.set(counter.get() + 1);
+                assert!(counter.get() > 1, "machine is warming up");
+                coffees - 1
+            }
+        })
+        .eventually(Duration::from_secs(1), Backoff::None)
+        .then("there are no coffees left", |coffees| {
+            assert_eq!(coffees, 0)
+        })
+        .await;
+
+    assert_eq!(calls.get(), 2);
+    let output = writer.output();
+    assert!(output.lines().nth(1).unwrap().ends_with(", 2 attempts)"));
+}
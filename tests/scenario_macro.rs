@@ -0,0 +1,76 @@
+// Given there are 2 coffees left in the machine
+// When I press the coffee button
+// Then I should be served a coffee
+// And there is 1 coffee left
+
+use common::SharedWriter;
+use gnerkinf::scenario;
+
+mod common;
+
+scenario! {
+    fn test_coffee_machine() {
+        Given "there are 2 coffees left in the machine" => || Machine { coffees: 2 };
+        When "I press the coffee button" => |mut machine| (machine.make_coffee(), machine);
+        Then "I should be served a coffee" => |(coffee, machine)| {
+            assert!(coffee.is_some());
+            machine
+        };
+        And "there is 1 coffee left" => |machine| assert_eq!(machine.coffees, 1)
+    }
+}
+
+scenario! {
+    fn test_coffee_machine_async_steps() {
+        Given "there are 2 coffees left in the machine" => async || Machine { coffees: 2 };
+        When "I press the coffee button twice" => async |mut machine| {
+            machine.make_coffee_slowly().await;
+            machine.make_coffee_slowly().await
+        };
+        Then "I should be served a coffee" => |coffee| assert!(coffee.is_some());
+    }
+}
+
+scenario! {
+    #[tokio::test]
+    async fn test_coffee_machine_on_tokio() {
+        Given "an empty machine" => || Machine { coffees: 0 };
+        When "I press the coffee button" => async |mut machine| machine.make_coffee_slowly().await;
+        Then "I should not be served a coffee" => |coffee| assert!(coffee.is_none());
+    }
+}
+
+#[test]
+fn test_step_text_is_formatted() {
+    let writer = SharedWriter::default();
+    let coffees = 3;
+    scenario!(writer.clone(),
+        Given "there are {coffees} coffees left in the machine" => || Machine { coffees };
+        When "I press the coffee button" => |mut machine| machine.make_coffee();
+        Then "I should be served a coffee" => |coffee| assert!(coffee.is_some());
+    );
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("Given there are 3 coffees left in the machine ("));
+    assert!(lines[1].starts_with("When I press the coffee button ("));
+    assert!(lines[2].starts_with("Then I should be served a coffee ("));
+}
+
+struct Machine {
+    coffees: u16,
+}
+
+impl Machine {
+    fn make_coffee(&mut self) -> Option<Coffee> {
+        self.coffees = self.coffees.checked_sub(1)?;
+        Some(Coffee)
+    }
+
+    async fn make_coffee_slowly(&mut self) -> Option<Coffee> {
+        self.make_coffee()
+    }
+}
+
+struct Coffee;
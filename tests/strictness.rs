@@ -0,0 +1,138 @@
+use common::SharedWriter;
+use std::time::Duration;
+
+use gnerkinf::{given_data, given_dataf, given_steps, Backoff, MultiPhase, Relaxed, Strict};
+
+mod common;
+
+#[test]
+fn test_multi_phase_scenario() {
+    let writer = SharedWriter::default();
+    given_data("a coffee machine with 2 coffees", 2, writer.clone())
+        .strictness::<MultiPhase>()
+        .when("I buy a coffee", |coffees| coffees - 1)
+        .then("there is 1 coffee left", |coffees| {
+            assert_eq!(coffees, 1);
+            coffees
+        })
+        .when("I buy another coffee", |coffees| coffees - 1)
+        .then("there are no coffees left", |coffees| {
+            assert_eq!(coffees, 0)
+        });
+
+    let keywords: Vec<String> = writer
+        .output()
+        .lines()
+        .filter_map(|line| Some(line.split_once(' ')?.0.to_string()))
+        .collect();
+    assert_eq!(keywords, ["Given", "When", "Then", "When", "Then"]);
+}
+
+#[test]
+fn test_relaxed_scenario() {
+    let writer = SharedWriter::default();
+    given_data("a coffee machine with 2 coffees", 2, writer.clone())
+        .strictness::<Relaxed>()
+        .then("it has coffees", |coffees| {
+            assert!(coffees > 0);
+            coffees
+        })
+        .when("I buy a coffee", |coffees| coffees - 1)
+        .then("there is 1 coffee left", |coffees| assert_eq!(coffees, 1));
+
+    assert!(writer
+        .output()
+        .lines()
+        .nth(1)
+        .unwrap()
+        .starts_with("Then it has coffees ("));
+}
+
+#[test]
+fn test_strictness_changed_back() {
+    given_data(
+        "a coffee machine with 2 coffees",
+        2,
+        SharedWriter::default(),
+    )
+    .strictness::<Relaxed>()
+    .strictness::<Strict>()
+    .when("I buy a coffee", |coffees| coffees - 1)
+    .then("there is 1 coffee left", |coffees| assert_eq!(coffees, 1));
+}
+
+#[tokio::test]
+async fn test_async_multi_phase_scenario() {
+    given_dataf(
+        "a coffee machine with 2 coffees",
+        2,
+        SharedWriter::default(),
+    )
+    .strictness::<MultiPhase>()
+    .whenf("I buy a coffee", |coffees| async move { coffees - 1 })
+    .then("there is 1 coffee left", |coffees| coffees)
+    .whenf("I buy another coffee", |coffees| async move { coffees - 1 })
+    .then("there are no coffees left", |coffees| {
+        assert_eq!(coffees, 0)
+    })
+    .await;
+}
+
+#[test]
+fn test_relaxed_wrappers() {
+    given_data(
+        "a coffee machine with 2 coffees",
+        2,
+        SharedWriter::default(),
+    )
+    .strictness::<Relaxed>()
+    .retry(2, Backoff::None)
+    .then("it has coffees", |coffees| coffees)
+    .world()
+    .when("I buy a coffee", |coffees, _| coffees - 1)
+    .then("there is 1 coffee left", |coffees| assert_eq!(coffees, 1));
+}
+
+#[test]
+fn test_multi_phase_wrappers() {
+    given_data(
+        "a coffee machine with 2 coffees",
+        2,
+        SharedWriter::default(),
+    )
+    .strictness::<MultiPhase>()
+    .when("I buy a coffee", |coffees| coffees - 1)
+    .then("there is 1 coffee left", |coffees| coffees)
+    .world()
+    .when("I buy another coffee", |coffees, _| coffees - 1)
+    .then("there is no coffee left", |coffees| coffees)
+    .eventually(Duration::from_secs(1), Backoff::None)
+    .when("I buy a coffee again", |coffees| coffees)
+    .then("there is still no coffee", |coffees| assert_eq!(coffees, 0));
+}
+
+#[test]
+fn test_multi_phase_recorded_steps() {
+    let writer = SharedWriter::default();
+    given_steps("a coffee machine with 2 coffees", || 2)
+        .strictness::<MultiPhase>()
+        .when("I buy a coffee", |coffees| coffees - 1)
+        .then("there is 1 coffee left", |coffees| coffees)
+        .when("I buy another coffee", |coffees| coffees - 1)
+        .then("there are no coffees left", |coffees| {
+            assert_eq!(coffees, 0)
+        })
+        .run(writer.clone());
+
+    let keywords: Vec<String> = writer
+        .output()
+        .lines()
+        .filter_map(|line| Some(line.split_once(' ')?.0.to_string()))
+        .collect();
+    assert_eq!(keywords, ["Given", "When", "Then", "When", "Then"]);
+}
+
+#[test]
+fn test_step_order_diagnostics() {
+    trybuild::TestCases::new().compile_fail("tests/strictness/*.rs");
+}
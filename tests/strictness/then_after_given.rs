@@ -0,0 +1,6 @@
+use gnerkinf::{given_data, create_stdout_writer};
+
+fn main() {
+    given_data("a coffee machine with 2 coffees", 2, create_stdout_writer())
+        .then("it has coffees", |coffees| assert!(coffees > 0));
+}
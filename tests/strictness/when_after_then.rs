@@ -0,0 +1,8 @@
+use gnerkinf::{given_data, create_stdout_writer};
+
+fn main() {
+    given_data("a coffee machine with 2 coffees", 2, create_stdout_writer())
+        .when("I buy a coffee", |coffees| coffees - 1)
+        .then("there is 1 coffee left", |coffees| coffees)
+        .when("I buy another coffee", |coffees| coffees - 1);
+}
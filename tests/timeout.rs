@@ -0,0 +1,111 @@
+use core::{future::pending, time::Duration};
+
+use common::{CatchUnwind, SharedWriter};
+use gnerkinf::{given_data, given_dataf};
+
+mod common;
+
+#[tokio::test]
+async fn test_step_timeout() {
+    let writer = SharedWriter::default();
+    let result = CatchUnwind::new(async {
+        given_dataf("a coffee machine", 1, writer.clone())
+            .step_timeout(Duration::from_millis(50))
+            .whenf("I press the coffee button", |coffees| async move {
+                pending::<()>().await;
+                coffees
+            })
+            .then("I should be served a coffee", |coffees| {
+                assert_eq!(coffees, 1)
+            })
+            .await;
+    })
+    .await;
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.starts_with("When I press the coffee button timed out after "));
+    let output = writer.output();
+    let last_step = output.lines().rev().nth(1).unwrap();
+    assert!(last_step.starts_with("When I press the coffee button ("));
+    assert!(last_step.ends_with("failed"));
+}
+
+#[tokio::test]
+async fn test_scenario_timeout() {
+    let result = CatchUnwind::new(async {
+        given_data("a coffee machine", 1, SharedWriter::default())
+            .scenario_timeout(Duration::from_millis(60))
+            .whenf("I press the coffee button", |coffees| async move {
+                tokio::time::sleep(Duration::from_millis(40)).await;
+                coffees
+            })
+            .await
+            .andf("I press it again", |coffees| async move {
+                tokio::time::sleep(Duration::from_millis(40)).await;
+                coffees
+            })
+            .await;
+    })
+    .await;
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.starts_with("And I press it again timed out after "));
+}
+
+#[tokio::test]
+async fn test_steps_within_timeout() {
+    given_dataf("a coffee machine", 1, SharedWriter::default())
+        .step_timeout(Duration::from_secs(5))
+        .scenario_timeout(Duration::from_secs(10))
+        .whenf("I press the coffee button", |coffees| async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            coffees - 1
+        })
+        .thenf("there are no coffees left", |coffees| async move {
+            assert_eq!(coffees, 0)
+        })
+        .await;
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_tokio_timer() {
+    let result = CatchUnwind::new(async {
+        given_dataf("a coffee machine", 1, SharedWriter::default())
+            .timer(gnerkinf::TokioTimer)
+            .step_timeout(Duration::from_millis(20))
+            .whenf("I press the coffee button", |coffees| async move {
+                pending::<()>().await;
+                coffees
+            })
+            .await;
+    })
+    .await;
+
+    assert!(result.is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_timed_steps_share_one_timer_thread() {
+    let threads = || {
+        let status = std::fs::read_to_string("/proc/self/status").unwrap();
+        let line = status.lines().find(|l| l.starts_with("Threads:")).unwrap();
+        line["Threads:".len()..].trim().parse::<usize>().unwrap()
+    };
+    let before = threads();
+    let mut context = given_dataf("a coffee machine", 0, SharedWriter::default())
+        .step_timeout(Duration::from_secs(60))
+        .await;
+    for _ in 0..64 {
+        context = context
+            .andf(
+                "I press the coffee button",
+                |coffees| async move { coffees + 1 },
+            )
+            .await;
+    }
+
+    context.when("I count the coffees", |coffees| assert_eq!(coffees, 64));
+    assert!(threads() < before + 32);
+}
@@ -0,0 +1,124 @@
+use core::time::Duration;
+use std::{cell::RefCell, rc::Rc, thread::sleep};
+
+use common::SharedWriter;
+use gnerkinf::{given, given_dataf, Keyword, Reporter, Status, StepReport, TextReporter};
+
+mod common;
+
+#[test]
+fn test_step_durations_are_printed() {
+    let writer = SharedWriter::default();
+    given("i have a bike", || 2, writer.clone())
+        .when("i add wheels", |wheels| wheels + 2)
+        .then("i should have 4 wheels", |wheels| assert_eq!(wheels, 4));
+
+    let output = writer.output();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("Given i have a bike ("));
+    assert!(lines[1].starts_with("When i add wheels ("));
+    assert!(lines[2].starts_with("Then i should have 4 wheels ("));
+    assert!(!output.contains("slow"));
+}
+
+#[test]
+fn test_slow_steps_are_highlighted() {
+    let writer = SharedWriter::default();
+    given(
+        "i have a bike",
+        || 2,
+        TextReporter::new(writer.clone()).slow_threshold(Duration::from_millis(20)),
+    )
+    .when("i wait for the shop to open", |wheels| {
+        sleep(Duration::from_millis(30));
+        wheels
+    })
+    .then("i should have 2 wheels", |wheels| assert_eq!(wheels, 2));
+
+    let output = writer.output();
+    let slow: Vec<&str> = output.lines().filter(|l| l.contains("slow")).collect();
+    assert_eq!(slow.len(), 1);
+    assert!(slow[0].starts_with("When i wait for the shop to open"));
+}
+
+#[tokio::test]
+async fn test_async_step_durations_include_awaiting() {
+    let steps = Rc::new(RefCell::new(Vec::new()));
+    let reporter: Box<dyn Reporter> = Box::new(CollectingReporter {
+        steps: steps.clone(),
+    });
+    given_dataf("i have a bike", 2, reporter)
+        .whenf("i wait for the shop to open", |wheels| async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            wheels
+        })
+        .then("i should have 2 wheels", |wheels| assert_eq!(wheels, 2))
+        .await;
+
+    let steps = steps.borrow();
+    assert_eq!(steps.len(), 3);
+    assert_eq!(steps[1].0, Keyword::When);
+    assert!(steps[1].1 >= Duration::from_millis(20));
+    assert!(steps.iter().all(|(_, _, status)| *status == Status::Passed));
+}
+
+#[test]
+fn test_failed_step_is_reported() {
+    let steps = Rc::new(RefCell::new(Vec::new()));
+    let reporter: Box<dyn Reporter> = Box::new(CollectingReporter {
+        steps: steps.clone(),
+    });
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        given("i have a bike", || 2, reporter)
+            .when("i add wheels", |wheels| wheels + 2)
+            .then("i should have 3 wheels", |wheels| assert_eq!(wheels, 3));
+    }));
+
+    assert!(result.is_err());
+    let steps = steps.borrow();
+    assert_eq!(steps.len(), 3);
+    assert_eq!(steps[2].0, Keyword::Then);
+    assert_eq!(steps[2].2, Status::Failed);
+}
+
+#[test]
+fn test_step_start_is_reported_before_the_closure_runs() {
+    let started = Rc::new(RefCell::new(Vec::new()));
+    let reporter: Box<dyn Reporter> = Box::new(StartedReporter {
+        started: started.clone(),
+    });
+    let running = started.clone();
+    given("i have a bike", || 2, reporter)
+        .when("i add wheels", move |wheels| {
+            assert_eq!(running.borrow().last().unwrap(), "When i add wheels");
+            wheels + 2
+        })
+        .then("i should have 4 wheels", |wheels| assert_eq!(wheels, 4));
+
+    assert_eq!(started.borrow().len(), 3);
+}
+
+struct CollectingReporter {
+    steps: Rc<RefCell<Vec<(Keyword, Duration, Status)>>>,
+}
+
+impl Reporter for CollectingReporter {
+    fn step(&mut self, step: &StepReport<'_>) {
+        self.steps
+            .borrow_mut()
+            .push((step.keyword, step.duration, step.status));
+    }
+}
+
+struct StartedReporter {
+    started: Rc<RefCell<Vec<String>>>,
+}
+
+impl Reporter for StartedReporter {
+    fn step_started(&mut self, keyword: Keyword, text: &str) {
+        self.started.borrow_mut().push(format!("{keyword} {text}"));
+    }
+
+    fn step(&mut self, _step: &StepReport<'_>) {}
+}
@@ -0,0 +1,73 @@
+use std::{
+    io::{Error, ErrorKind, Write},
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use gnerkinf::{given, TextReporter, WriteErrorPolicy};
+
+#[test]
+fn test_write_errors_do_not_fail_steps() {
+    given("i have a bike", || 2, BrokenWriter)
+        .when("i add wheels", |wheels| wheels + 2)
+        .then("i should have 4 wheels", |wheels| assert_eq!(wheels, 4));
+}
+
+#[test]
+fn test_write_errors_ignored() {
+    given(
+        "i have a bike",
+        || 2,
+        TextReporter::new(BrokenWriter).on_write_error(WriteErrorPolicy::Ignore),
+    )
+    .when("i add wheels", |wheels| wheels + 2)
+    .then("i should have 4 wheels", |wheels| assert_eq!(wheels, 4));
+}
+
+#[test]
+fn test_write_errors_panic_at_scenario_end() {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        given(
+            "i have a bike",
+            || 2,
+            TextReporter::new(BrokenWriter).on_write_error(WriteErrorPolicy::Panic),
+        )
+        .when("i add wheels", |wheels| wheels + 2)
+        .then("i should have 4 wheels", |wheels| assert_eq!(wheels, 4));
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(
+        message,
+        "gnerkinf: 5 report writes failed, first error: pipe closed"
+    );
+}
+
+#[test]
+fn test_write_errors_do_not_hide_step_failure() {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        given(
+            "i have a bike",
+            || 2,
+            TextReporter::new(BrokenWriter).on_write_error(WriteErrorPolicy::Panic),
+        )
+        .when("i add wheels", |wheels| wheels + 2)
+        .then("i should have 3 wheels", |wheels| {
+            assert_eq!(wheels, 3, "wrong wheels")
+        });
+    }));
+
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("wrong wheels"));
+}
+
+struct BrokenWriter;
+
+impl Write for BrokenWriter {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(Error::new(ErrorKind::BrokenPipe, "pipe closed"))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Err(Error::new(ErrorKind::BrokenPipe, "pipe closed"))
+    }
+}